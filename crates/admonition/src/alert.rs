@@ -0,0 +1,41 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    parser::{ core::CoreRule, inline::InlineRoot },
+    plugins::cmark::block::{ blockquote::Blockquote, paragraph::Paragraph }
+};
+use wikimd_common::strip_start;
+use crate::AdmonitionOptions;
+
+// Splits `[!TYPE]` off the first line of a paragraph, returning the type. GitHub only allows the marker
+// on a line of its own.
+fn marker(content: &str) -> Option<(&str, usize)> {
+    let line_end = content.find('\n').unwrap_or(content.len());
+    let kind = content[..line_end].trim_end().strip_prefix("[!")?.strip_suffix(']')?;
+    Some((kind, line_end))
+}
+
+// Runs between block and inline parsing, so that the marker is gone before it could become a link.
+pub struct AlertRule;
+
+impl CoreRule for AlertRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<AdmonitionOptions>().unwrap();
+        root.walk_mut(|node, _| {
+            if !node.is::<Blockquote>() { return; }
+            let Some(paragraph) = node.children.first_mut().filter(|child| child.is::<Paragraph>()) else { return };
+            let Some(inline) = paragraph.children.first_mut().and_then(|child| child.cast_mut::<InlineRoot>()) else { return };
+            let Some((kind, length)) = marker(&inline.content) else { return };
+            if !options.types.contains_key(&kind.to_lowercase()) { return; }
+
+            let mut admonition = options.admonition(kind, None);
+            strip_start(inline, length);
+            if inline.content.is_empty() {
+                node.children.remove(0);
+            }
+            admonition.children = std::mem::take(&mut node.children);
+            admonition.srcmap = node.srcmap;
+            admonition.attrs.append(&mut node.attrs);
+            *node = admonition;
+        });
+    }
+}
@@ -0,0 +1,65 @@
+use markdown_it::{
+    Node,
+    parser::block::{ BlockRule, BlockState }
+};
+use crate::AdmonitionOptions;
+
+// `!!! type "Title"`, followed by the contents indented by 4 spaces.
+pub struct AdmonitionBlockScanner;
+
+impl AdmonitionBlockScanner {
+    // Returns the type, and the title if there's one in quotes. An empty title (`""`) means the
+    // admonition has no title at all, rather than the one of its type.
+    fn header(state: &BlockState) -> Option<(String, Option<String>)> {
+        if state.line_indent(state.line) >= 4 { return None; }
+        let rest = state.get_line(state.line).strip_prefix("!!!")?;
+        if !rest.starts_with([' ', '\t']) { return None; }
+        let rest = rest.trim();
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let kind = &rest[..end];
+        if kind.is_empty() || !kind.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') { return None; }
+
+        let title = rest[end..].trim_start();
+        if title.is_empty() { return Some((kind.to_string(), None)); }
+        let title = title.strip_prefix('"')?.strip_suffix('"')?;
+        Some((kind.to_string(), Some(title.to_string())))
+    }
+}
+
+impl BlockRule for AdmonitionBlockScanner {
+    fn check(state: &mut BlockState) -> Option<()> {
+        Self::header(state).map(|_| ())
+    }
+
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        let (kind, title) = Self::header(state)?;
+        let options = state.md.ext.get::<AdmonitionOptions>().unwrap();
+        let node = options.admonition(&kind, title);
+
+        // The contents are every line after the header which is indented, along with the empty lines between
+        // them; empty lines after the last one are left to whatever comes next.
+        let start = state.line;
+        let mut end = start + 1;
+        let mut line = start + 1;
+        while line < state.line_max {
+            if !state.is_empty(line) {
+                if state.line_indent(line) < 4 { break; }
+                end = line + 1;
+            }
+            line += 1;
+        }
+
+        let old_node = std::mem::replace(&mut state.node, node);
+        let old_line_max = state.line_max;
+        state.line = start + 1;
+        state.line_max = end;
+        state.blk_indent += 4;
+        state.md.block.tokenize(state);
+        state.blk_indent -= 4;
+        state.line_max = old_line_max;
+        state.line = start;
+
+        Some((std::mem::replace(&mut state.node, old_node), end - start))
+    }
+}
@@ -0,0 +1,134 @@
+mod alert;
+mod block;
+use crate::alert::AlertRule;
+use crate::block::AdmonitionBlockScanner;
+
+use markdown_it::{
+    MarkdownIt, Node, NodeValue, Renderer,
+    parser::{
+        block::builtin::BlockParserRule,
+        extset::MarkdownItExt,
+        inline::builtin::InlineParserRule
+    },
+    plugins::cmark::block::{ code::CodeScanner, reference::ReferenceScanner }
+};
+use std::collections::HashMap;
+
+/// How admonitions of one type look.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct AdmonitionType {
+    pub class: String, // added after AdmonitionOptions::class.
+    pub title: String, // used when the admonition doesn't have a title of its own.
+    pub icon: Option<String> // raw HTML put before the title, like an <svg> or an emoji.
+}
+
+impl AdmonitionType {
+    fn new(class: &str, title: &str) -> Self {
+        Self { class: class.to_string(), title: title.to_string(), icon: None }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct AdmonitionOptions {
+    pub class: String, // class of every admonition, followed by the class of its type.
+    pub title_class: String,
+    pub types: HashMap<String, AdmonitionType>, // keyed by the lowercase name of the type. `!!!` blocks of other
+                                                // types get the type's name as their class and title.
+    pub blocks: bool, // !!! note "Title" blocks, as used by markdown2 and Python-Markdown.
+    pub alerts: bool // GitHub's > [!NOTE] alerts, only for the types listed in `types`.
+}
+
+impl Default for AdmonitionOptions {
+    fn default() -> Self {
+        Self {
+            class: "admonition".to_string(),
+            title_class: "admonition-title".to_string(),
+            types: HashMap::from([
+                ("note".to_string(), AdmonitionType::new("note", "Note")),
+                ("tip".to_string(), AdmonitionType::new("tip", "Tip")),
+                ("important".to_string(), AdmonitionType::new("important", "Important")),
+                ("warning".to_string(), AdmonitionType::new("warning", "Warning")),
+                ("caution".to_string(), AdmonitionType::new("caution", "Caution"))
+            ]),
+            blocks: true,
+            alerts: true
+        }
+    }
+}
+impl MarkdownItExt for AdmonitionOptions {}
+
+impl AdmonitionOptions {
+    // Makes the node for an admonition of the given type, with `title` overriding the type's.
+    fn admonition(&self, kind: &str, title: Option<String>) -> Node {
+        let kind = kind.to_lowercase();
+        let known = self.types.get(&kind);
+        let class = known.map_or(kind.clone(), |t| t.class.clone());
+        let title = title.unwrap_or_else(|| match known {
+            Some(t) => t.title.clone(),
+            None => capitalize(&kind)
+        });
+
+        let mut node = Node::new(Admonition {
+            title: (!title.is_empty()).then_some(title),
+            icon: known.and_then(|t| t.icon.clone()),
+            title_class: self.title_class.clone(),
+            kind
+        });
+        let classes: Vec<&str> = [self.class.as_str(), &class].into_iter().filter(|c| !c.is_empty()).collect();
+        if !classes.is_empty() {
+            node.attrs.push(("class", classes.join(" ")));
+        }
+        node
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// A note, warning, etc. set apart from the rest of the page. Its contents are its children.
+#[derive(Debug)]
+pub struct Admonition {
+    pub kind: String, // lowercase name of the type, like "note".
+    pub title: Option<String>,
+    pub icon: Option<String>,
+    title_class: String
+}
+
+impl NodeValue for Admonition {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("aside", &node.attrs);
+        fmt.cr();
+        if let Some(title) = &self.title {
+            fmt.open("p", &[("class", self.title_class.clone())]);
+            if let Some(icon) = &self.icon {
+                fmt.text_raw(icon);
+            }
+            fmt.text(title);
+            fmt.close("p");
+            fmt.cr();
+        }
+        fmt.contents(&node.children);
+        fmt.cr();
+        fmt.close("aside");
+        fmt.cr();
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, AdmonitionOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: AdmonitionOptions) {
+    if options.blocks {
+        md.block.add_rule::<AdmonitionBlockScanner>().after::<CodeScanner>().before::<ReferenceScanner>();
+    }
+    if options.alerts {
+        md.add_rule::<AlertRule>().after::<BlockParserRule>().before::<InlineParserRule>();
+    }
+    md.ext.insert(options);
+}
@@ -0,0 +1,22 @@
+use criterion::{ criterion_group, criterion_main, BenchmarkId, Criterion, Throughput };
+use wikimd_bench::{ parsers, wiki_page };
+
+// Parses and renders pages of a few sizes with every parser, so each plugin can be compared against
+// plain cmark on the same page.
+fn parse_and_render(c: &mut Criterion) {
+    let parsers = parsers();
+    for sections in [10, 100, 500] {
+        let page = wiki_page(sections);
+        let mut group = c.benchmark_group(format!("{sections} sections"));
+        group.throughput(Throughput::Bytes(page.len() as u64));
+        for (name, md) in &parsers {
+            group.bench_with_input(BenchmarkId::from_parameter(name), &page, |b, page| {
+                b.iter(|| md.parse(page).render());
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse_and_render);
+criterion_main!(benches);
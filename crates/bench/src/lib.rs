@@ -0,0 +1,102 @@
+//! Documents and parsers for the benchmarks in `benches/`.
+
+use wikimd::WikiMd;
+use wikimd::markdown_it::{ self, MarkdownIt };
+
+// Formulas taken from the wiki, from trivial to the heavier ones.
+const FORMULAS: &[&str] = &[
+    "x^2",
+    "\\alpha_{2}^{4}",
+    "\\frac{a + b}{c - d}",
+    "\\sum_{i=0}^{n} i = \\frac{n(n + 1)}{2}",
+    "\\int_{0}^{\\infty} e^{-x^2} dx = \\frac{\\sqrt{\\pi}}{2}"
+];
+
+/// A wiki page with `sections` top-level sections, each with a few subsections, footnote references,
+/// inline and block formulas, lists and code, along with something for each of the other plugins: an
+/// admonition, a spoiler, a task list, a wiki table, an image and sub/superscripts. The footnote definitions
+/// are grouped at the end of every fourth section. The same number of sections always gives the same page.
+pub fn wiki_page(sections: usize) -> String {
+    let mut page = String::from("# Page title\n\n[TOC]\n\n");
+    let mut definitions = String::new();
+    for section in 1..=sections {
+        let formula = FORMULAS[section % FORMULAS.len()];
+        page += &format!("## Section {section}\n\n");
+        page += &format!(
+            "Lorem ipsum dolor sit amet, *consectetur* adipiscing elit.[^note-{section}] Sed do eiusmod tempor \
+            incididunt ut labore, where ${formula}$ holds, et dolore magna aliqua. See [the docs](https://nekoweb.org) \
+            for `more`.\n\n"
+        );
+        for subsection in 1..=3 {
+            page += &format!("### Subsection {section}.{subsection}\n\n");
+            page += "Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea \
+                commodo consequat.\n\n- Duis aute irure dolor\n- In reprehenderit in **voluptate**\n- Velit esse \
+                cillum dolore\n\n";
+        }
+        page += &format!(
+            "!!! note \"Section {section}\"\n    H~2~O boils at 100 \"degrees\" -- at 1 atm^2^...\n\n\
+            >! The butler ||did it|| in section {section}.\n\n\
+            - [x] Written\n- [ ] Reviewed\n\n\
+            ||~ Name || Value ||\n|| a || {section} ||\n\n\
+            ![Cat {section}](cat.png){{width=\"320\"}}\n\n"
+        );
+        page += &format!("$${formula}$$\n\n```rust\nfn main() {{\n    println!(\"{section}\");\n}}\n```\n\n");
+
+        definitions += &format!("[^note-{section}]: Footnote for section {section}, with ${formula}$ in it.\n");
+        if section % 4 == 0 || section == sections {
+            page += &format!("#### Notes\n\n{definitions}\n");
+            definitions.clear();
+        }
+    }
+    page
+}
+
+/// Parsers to compare: plain CommonMark, each plugin on its own, the wiki's markdown2 extras, and every
+/// plugin at once.
+pub fn parsers() -> Vec<(&'static str, MarkdownIt)> {
+    let with = |add: fn(&mut MarkdownIt)| {
+        let mut md = MarkdownIt::new();
+        markdown_it::plugins::cmark::add(&mut md);
+        add(&mut md);
+        md
+    };
+    vec![
+        ("cmark", with(|_| {})),
+        ("toc", with(wikimd::toc::add)),
+        ("footnote", with(wikimd::footnote::add)),
+        ("latex", with(wikimd::latex::add)),
+        ("admonition", with(wikimd::admonition::add)),
+        ("spoiler", with(wikimd::spoiler::add)),
+        ("tasklist", with(wikimd::tasklist::add)),
+        ("wikitable", with(wikimd::wikitable::add)),
+        ("supsub", with(wikimd::supsub::add)),
+        ("lazyload", with(wikimd::lazyload::add)),
+        ("smartypants", with(wikimd::smartypants::add)),
+        ("linkattrs", with(wikimd::linkattrs::add)),
+        ("markdown2", WikiMd::markdown2_compat().build()),
+        ("all", WikiMd::markdown2_compat()
+            .supsub(Default::default())
+            .lazyload(Default::default())
+            .smartypants(Default::default())
+            .build())
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_uses_every_plugin() {
+        let (_, md) = parsers().pop().unwrap();
+        let html = md.parse(&wiki_page(8)).render();
+        assert!(html.contains("<nav class=\"table_of_contents\">"));
+        assert_eq!(html.matches("<ul class=\"footnotes-list\">").count(), 2);
+        assert_eq!(html.matches("<math display=\"block\">").count(), 8);
+        assert_eq!(html.matches("<table>").count(), 8);
+        assert_eq!(html.matches("loading=\"lazy\"").count(), 8);
+        assert_eq!(html.matches("<sub>2</sub>").count(), 8);
+        assert_eq!(html.matches("<span class=\"spoiler\">did it</span>").count(), 8);
+        assert!(html.contains("“degrees”") && html.contains("target=\"_blank\""));
+    }
+}
@@ -0,0 +1,312 @@
+mod options;
+mod template;
+
+use std::io::{ Read, Write };
+use std::path::{ Path, PathBuf };
+use std::process::ExitCode;
+use clap::Parser;
+use walkdir::WalkDir;
+use wikimd::WikiMd;
+use wikimd::markdown_it::MarkdownIt;
+use wikimd::toc::table_of_contents;
+use crate::options::{ PluginOptions, PLUGINS };
+
+/// Renders Markdown to HTML with the WikiMD plugins.
+#[derive(Parser)]
+#[command(name = "wikimd", version)]
+struct Cli {
+    /// Markdown files or directories to convert. Reads from stdin when none are given, or for `-`.
+    inputs: Vec<PathBuf>,
+
+    /// Write the HTML to this file instead of stdout.
+    #[arg(short, long, conflicts_with = "out_dir")]
+    output: Option<PathBuf>,
+
+    /// Convert every file into this directory, keeping the layout of input directories. Needed when
+    /// converting directories.
+    #[arg(short = 'd', long, requires = "inputs")]
+    out_dir: Option<PathBuf>,
+
+    /// Enable the same plugins as the wiki's markdown2 extras.
+    #[arg(long)]
+    markdown2: bool,
+
+    /// Enable the table of contents.
+    #[arg(long)]
+    toc: bool,
+
+    /// Enable footnotes.
+    #[arg(long)]
+    footnotes: bool,
+
+    /// Enable LaTeX formulas.
+    #[arg(long)]
+    latex: bool,
+
+    /// Enable `!!! note` admonitions and GitHub's `> [!NOTE]` alerts.
+    #[arg(long)]
+    admonitions: bool,
+
+    /// Enable `>! block` and `||inline||` spoilers.
+    #[arg(long)]
+    spoilers: bool,
+
+    /// Enable `- [ ]` and `- [x]` task lists.
+    #[arg(long)]
+    tasklists: bool,
+
+    /// Open external links in a new tab, with `rel="noopener nofollow ugc"` and an `external` class.
+    #[arg(long)]
+    linkattrs: bool,
+
+    /// Pass raw HTML through.
+    #[arg(long)]
+    html: bool,
+
+    /// Enable ~~strikethrough~~.
+    #[arg(long)]
+    strikethrough: bool,
+
+    /// Enable tables.
+    #[arg(long)]
+    tables: bool,
+
+    /// Enable markdown2's `|| a || b ||` tables.
+    #[arg(long)]
+    wikitables: bool,
+
+    /// Enable ^superscript^ and ~subscript~.
+    #[arg(long)]
+    supsub: bool,
+
+    /// Load images lazily.
+    #[arg(long)]
+    lazyload: bool,
+
+    /// Turn "quotes", -- dashes and ellipses... into typographic ones.
+    #[arg(long)]
+    smartypants: bool,
+
+    /// TOML file with plugin options, in tables named after the plugins' flags (`[toc]`, `[footnotes]`, ...).
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Set a plugin option, e.g. `--set toc.min_level=2`. Overrides the config file.
+    #[arg(short, long = "set", value_name = "PLUGIN.OPTION=VALUE")]
+    set: Vec<String>,
+
+    /// Output a whole HTML document instead of a fragment.
+    #[arg(long)]
+    standalone: bool,
+
+    /// Template for `--standalone`, with `{{title}}`, `{{toc}}` and `{{content}}` in it. A template with `{{toc}}`
+    /// places the table of contents itself, so it's left out of the page unless `toc.inject` is set.
+    #[arg(short, long, requires = "standalone")]
+    template: Option<PathBuf>
+}
+
+// The page, and anything the template needs from it.
+struct Page {
+    title: Option<String>,
+    toc: String,
+    content: String
+}
+
+fn render(md: &MarkdownIt, markdown: &str) -> Page {
+    let root = md.parse(markdown);
+    Page {
+        title: template::title(&root),
+        toc: table_of_contents(&root).map(|toc| toc.render()).unwrap_or_default(),
+        content: root.render()
+    }
+}
+
+impl Cli {
+    fn parser(&self, template: &str) -> Result<MarkdownIt, String> {
+        let mut options = match &self.config {
+            Some(path) => PluginOptions::from_file(path)?,
+            None => PluginOptions::default()
+        };
+        for assignment in &self.set {
+            options.set(assignment)?;
+        }
+
+        if self.standalone && template.contains("{{toc}}") && (self.toc || self.markdown2 || options.has("toc")) {
+            options.enable("toc");
+            options.set_default("toc", "inject", false);
+        }
+        // Options replace the ones markdown2_compat() picked, so its ids are kept unless other options are given.
+        if self.markdown2 {
+            options.set_default("toc", "slugger", "markdown2");
+            options.set_default("toc", "duplicate_suffix_start", 2);
+        }
+
+        let mut wiki = if self.markdown2 { WikiMd::markdown2_compat() } else { WikiMd::new() };
+        wiki = wiki.html(self.markdown2 || self.html)
+            .strikethrough(self.markdown2 || self.strikethrough)
+            .tables(self.markdown2 || self.tables)
+            .latex(self.markdown2 || self.latex);
+        if self.wikitables || options.has("wikitables") {
+            if let Some(wikitables) = options.wikitables()? {
+                wiki = wiki.wiki_tables(wikitables);
+            } else if !self.markdown2 {
+                wiki = wiki.wiki_tables(Default::default());
+            }
+        }
+        if self.toc || options.has("toc") {
+            if let Some(toc) = options.toc()? {
+                wiki = wiki.toc(toc);
+            } else if !self.markdown2 {
+                wiki = wiki.toc(Default::default());
+            }
+        }
+        if self.footnotes || options.has("footnotes") {
+            if let Some(footnotes) = options.footnotes()? {
+                wiki = wiki.footnotes(footnotes);
+            } else if !self.markdown2 {
+                wiki = wiki.footnotes(Default::default());
+            }
+        }
+        if self.admonitions || options.has("admonitions") {
+            if let Some(admonitions) = options.admonitions()? {
+                wiki = wiki.admonitions(admonitions);
+            } else if !self.markdown2 {
+                wiki = wiki.admonitions(Default::default());
+            }
+        }
+        if self.spoilers || options.has("spoilers") {
+            if let Some(spoilers) = options.spoilers()? {
+                wiki = wiki.spoilers(spoilers);
+            } else if !self.markdown2 {
+                wiki = wiki.spoilers(Default::default());
+            }
+        }
+        if self.tasklists || options.has("tasklists") {
+            if let Some(tasklists) = options.tasklists()? {
+                wiki = wiki.task_lists(tasklists);
+            } else if !self.markdown2 {
+                wiki = wiki.task_lists(Default::default());
+            }
+        }
+        if self.linkattrs || options.has("linkattrs") {
+            if let Some(linkattrs) = options.linkattrs()? {
+                wiki = wiki.link_attributes(linkattrs);
+            } else if !self.markdown2 {
+                wiki = wiki.link_attributes(Default::default());
+            }
+        }
+        if self.supsub || options.has("supsub") {
+            wiki = wiki.supsub(options.supsub()?.unwrap_or_default());
+        }
+        if self.lazyload || options.has("lazyload") {
+            wiki = wiki.lazyload(options.lazyload()?.unwrap_or_default());
+        }
+        if self.smartypants || options.has("smartypants") {
+            wiki = wiki.smartypants(options.smartypants()?.unwrap_or_default());
+        }
+        if let Some(plugin) = options.unknown().next() {
+            return Err(format!("unknown plugin `{}` in the options, expected one of {}", plugin, PLUGINS.join(", ")));
+        }
+        Ok(wiki.build())
+    }
+
+    fn template(&self) -> Result<String, String> {
+        match &self.template {
+            Some(path) => std::fs::read_to_string(path)
+                .map_err(|e| format!("couldn't read {}: {}", path.display(), e)),
+            None => Ok(template::DEFAULT_TEMPLATE.to_string())
+        }
+    }
+
+    fn write(&self, page: Page, fallback_title: &str, template: &str) -> String {
+        if !self.standalone {
+            return page.content;
+        }
+        let title = page.title.unwrap_or_else(|| fallback_title.to_string());
+        template::fill(template, &title, &page.toc, &page.content)
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    if path == Path::new("-") {
+        let mut markdown = String::new();
+        std::io::stdin().read_to_string(&mut markdown).map_err(|e| format!("couldn't read stdin: {}", e))?;
+        return Ok(markdown);
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("md" | "markdown"))
+}
+
+// Pairs every Markdown file to convert with where its HTML goes, relative to the output directory.
+fn batch(inputs: &[PathBuf]) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if !input.is_dir() {
+            let name = input.file_name().ok_or_else(|| format!("{} isn't a file", input.display()))?;
+            files.push((input.clone(), Path::new(name).with_extension("html")));
+            continue;
+        }
+        for entry in WalkDir::new(input).sort_by_file_name() {
+            let entry = entry.map_err(|e| e.to_string())?;
+            if !entry.file_type().is_file() || !is_markdown(entry.path()) { continue; }
+            let relative = entry.path().strip_prefix(input).unwrap().with_extension("html");
+            files.push((entry.path().to_path_buf(), relative));
+        }
+    }
+    Ok(files)
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let template = cli.template()?;
+    let md = cli.parser(&template)?;
+
+    if let Some(out_dir) = &cli.out_dir {
+        if cli.inputs.iter().any(|input| input == Path::new("-")) {
+            return Err("stdin can't be converted with --out-dir, as there's no file name to give its HTML".to_string());
+        }
+        for (input, output) in batch(&cli.inputs)? {
+            let page = render(&md, &read(&input)?);
+            let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            let output = out_dir.join(output);
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("couldn't create {}: {}", parent.display(), e))?;
+            }
+            std::fs::write(&output, cli.write(page, &stem, &template))
+                .map_err(|e| format!("couldn't write {}: {}", output.display(), e))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = cli.inputs.iter().find(|input| input.is_dir()) {
+        return Err(format!("{} is a directory, which needs --out-dir", dir.display()));
+    }
+    let inputs = if cli.inputs.is_empty() { vec![PathBuf::from("-")] } else { cli.inputs.clone() };
+    let mut markdown = String::new();
+    for input in &inputs {
+        // A blank line between files, so that the last block of one doesn't run on into the next.
+        if !markdown.is_empty() {
+            markdown += "\n\n";
+        }
+        markdown += &read(input)?;
+    }
+    let stem = inputs[0].file_stem().and_then(|s| s.to_str()).filter(|s| *s != "-").unwrap_or("Untitled");
+    let html = cli.write(render(&md, &markdown), stem, &template);
+
+    match &cli.output {
+        Some(path) => std::fs::write(path, html).map_err(|e| format!("couldn't write {}: {}", path.display(), e)),
+        None => std::io::stdout().write_all(html.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("wikimd: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
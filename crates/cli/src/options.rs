@@ -0,0 +1,124 @@
+use std::path::Path;
+use toml::{ Table, Value };
+use wikimd::toc::TOCOptions;
+use wikimd::footnote::FootnoteOptions;
+use wikimd::admonition::AdmonitionOptions;
+use wikimd::spoiler::SpoilerOptions;
+use wikimd::tasklist::TaskListOptions;
+use wikimd::supsub::SupSubOptions;
+use wikimd::lazyload::LazyLoadOptions;
+use wikimd::smartypants::SmartyPantsOptions;
+use wikimd::wikitable::WikiTableOptions;
+use wikimd::linkattrs::LinkAttributesOptions;
+
+pub const PLUGINS: [&str; 10] = [
+    "toc", "footnotes", "admonitions", "spoilers", "tasklists", "wikitables", "linkattrs", "supsub", "lazyload",
+    "smartypants"
+];
+
+/// Options for the plugins, read from the tables of a TOML file named after them (`[toc]`, `[footnotes]`, ...). The
+/// fields are the same as in each plugin's options struct.
+#[derive(Default)]
+pub struct PluginOptions {
+    table: Table
+}
+
+impl PluginOptions {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+        let table = contents.parse::<Table>()
+            .map_err(|e| format!("couldn't parse {}: {}", path.display(), e))?;
+        Ok(PluginOptions { table })
+    }
+
+    /// Sets a single option from `plugin.field=value`. The value is read as TOML, falling back to a string
+    /// when it isn't valid TOML, so `toc.toc_class=contents` works without quotes.
+    pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+        let (key, value) = assignment.split_once('=')
+            .ok_or_else(|| format!("expected plugin.option=value, got `{}`", assignment))?;
+        let (plugin, field) = key.trim().split_once('.')
+            .ok_or_else(|| format!("expected plugin.option=value, got `{}`", assignment))?;
+        let value = format!("value = {}", value.trim()).parse::<Table>()
+            .ok()
+            .and_then(|mut parsed| parsed.remove("value"))
+            .unwrap_or_else(|| Value::String(value.trim().to_string()));
+
+        let section = self.table.entry(plugin.to_string()).or_insert_with(|| Value::Table(Table::new()));
+        match section {
+            Value::Table(section) => { section.insert(field.to_string(), value); },
+            _ => return Err(format!("`{}` isn't a table of options", plugin))
+        }
+        Ok(())
+    }
+
+    /// Enables a plugin as if it had been given options, keeping any it already has.
+    pub fn enable(&mut self, plugin: &str) {
+        self.table.entry(plugin.to_string()).or_insert_with(|| Value::Table(Table::new()));
+    }
+
+    /// Sets an option of a plugin which has options, unless they already set it themselves.
+    pub fn set_default(&mut self, plugin: &str, field: &str, value: impl Into<Value>) {
+        if let Some(Value::Table(section)) = self.table.get_mut(plugin) {
+            section.entry(field).or_insert_with(|| value.into());
+        }
+    }
+
+    pub fn has(&self, plugin: &str) -> bool {
+        self.table.contains_key(plugin)
+    }
+
+    /// Tables which don't belong to any plugin.
+    pub fn unknown(&self) -> impl Iterator<Item = &str> {
+        self.table.keys().map(String::as_str).filter(|plugin| !PLUGINS.contains(plugin))
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(&self, plugin: &str) -> Result<Option<T>, String> {
+        match self.table.get(plugin) {
+            Some(section) => section.clone().try_into()
+                .map(Some)
+                .map_err(|e| format!("invalid options for {}: {}", plugin, e)),
+            None => Ok(None)
+        }
+    }
+
+    pub fn toc(&self) -> Result<Option<TOCOptions>, String> {
+        self.get("toc")
+    }
+
+    pub fn footnotes(&self) -> Result<Option<FootnoteOptions>, String> {
+        self.get("footnotes")
+    }
+
+    pub fn admonitions(&self) -> Result<Option<AdmonitionOptions>, String> {
+        self.get("admonitions")
+    }
+
+    pub fn spoilers(&self) -> Result<Option<SpoilerOptions>, String> {
+        self.get("spoilers")
+    }
+
+    pub fn tasklists(&self) -> Result<Option<TaskListOptions>, String> {
+        self.get("tasklists")
+    }
+
+    pub fn wikitables(&self) -> Result<Option<WikiTableOptions>, String> {
+        self.get("wikitables")
+    }
+
+    pub fn linkattrs(&self) -> Result<Option<LinkAttributesOptions>, String> {
+        self.get("linkattrs")
+    }
+
+    pub fn supsub(&self) -> Result<Option<SupSubOptions>, String> {
+        self.get("supsub")
+    }
+
+    pub fn lazyload(&self) -> Result<Option<LazyLoadOptions>, String> {
+        self.get("lazyload")
+    }
+
+    pub fn smartypants(&self) -> Result<Option<SmartyPantsOptions>, String> {
+        self.get("smartypants")
+    }
+}
@@ -0,0 +1,60 @@
+use wikimd::markdown_it::Node;
+use wikimd::markdown_it::plugins::cmark::block::heading::ATXHeading;
+use wikimd::markdown_it::plugins::cmark::block::lheading::SetextHeader;
+
+/// Used by `--standalone` when no `--template` is given.
+pub const DEFAULT_TEMPLATE: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{{title}}</title>
+</head>
+<body>
+{{content}}</body>
+</html>
+";
+
+/// Fills in `{{title}}` (escaped), `{{toc}}` (the table of contents, if the plugin is enabled) and
+/// `{{content}}` (the rendered page). The template is filled in a single pass, so placeholders written in the page
+/// itself are left as they are.
+pub fn fill(template: &str, title: &str, toc: &str, content: &str) -> String {
+    let title = escape(title);
+    let placeholders = [("{{title}}", title.as_str()), ("{{toc}}", toc), ("{{content}}", content)];
+    let mut result = String::with_capacity(template.len() + content.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match placeholders.iter().find(|(placeholder, _)| rest.starts_with(placeholder)) {
+            Some((placeholder, value)) => {
+                result.push_str(value);
+                rest = &rest[placeholder.len()..];
+            },
+            None => {
+                result.push_str("{{");
+                rest = &rest[2..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Text of the page's first h1.
+pub fn title(root: &Node) -> Option<String> {
+    let mut title = None;
+    root.walk(|node, _| {
+        if title.is_some() { return; }
+        let level = node.cast::<ATXHeading>().map(|h| h.level)
+            .or_else(|| node.cast::<SetextHeader>().map(|h| h.level));
+        if level == Some(1) {
+            title = Some(node.collect_text());
+        }
+    });
+    title
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
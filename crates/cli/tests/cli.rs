@@ -0,0 +1,156 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{ Command, Output, Stdio };
+
+fn wikimd(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wikimd"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wikimd-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn stdin_to_stdout() {
+    assert_eq!(stdout(wikimd(&[], "Some *text*")), "<p>Some <em>text</em></p>\n");
+    assert_eq!(stdout(wikimd(&["-"], "$x$")), "<p>$x$</p>\n");
+}
+
+#[test]
+fn several_inputs() {
+    let dir = temp_dir("inputs");
+    std::fs::write(dir.join("a.md"), "a\n\nb").unwrap();
+    std::fs::write(dir.join("b.md"), "c").unwrap();
+    let html = stdout(wikimd(&[dir.join("a.md").to_str().unwrap(), dir.join("b.md").to_str().unwrap()], ""));
+    assert_eq!(html, "<p>a</p>\n<p>b</p>\n<p>c</p>\n");
+}
+
+#[test]
+fn plugin_flags() {
+    let html = stdout(wikimd(&["--html", "--smartypants"], "<span title=\"a\">\"q\"</span>"));
+    assert_eq!(html, "<p><span title=\"a\">“q”</span></p>\n");
+
+    let html = stdout(wikimd(&["--latex"], "$x$"));
+    assert!(html.contains("<math"), "{}", html);
+
+    let html = stdout(wikimd(&["--toc", "--footnotes"], "[TOC]\n\n## A\nb[^c]\n\n[^c]: d"));
+    assert!(html.contains(r#"<nav class="table_of_contents">"#), "{}", html);
+    assert!(html.contains(r#"class="footnotes-list""#), "{}", html);
+
+    let html = stdout(wikimd(&["--supsub", "--set", "supsub.shorthand=false"], "H~2~O x^2"));
+    assert_eq!(html, "<p>H<sub>2</sub>O x^2</p>\n");
+
+    let html = stdout(wikimd(&["--lazyload"], "![a](a.png){width=10}"));
+    assert_eq!(html, "<p><img width=\"10\" loading=\"lazy\" decoding=\"async\" src=\"a.png\" alt=\"a\"></p>\n");
+
+    let html = stdout(wikimd(&["--admonitions", "--set", "admonitions.title_class=title"], "> [!NOTE]\n> a"));
+    assert!(html.contains(r#"<p class="title">Note</p>"#), "{}", html);
+
+    let html = stdout(wikimd(&["--spoilers", "--set", "spoilers.summary=Show"], ">! a ||b||"));
+    assert!(html.contains("<summary>Show</summary>") && html.contains(r#"<span class="spoiler">b</span>"#), "{}", html);
+
+    let html = stdout(wikimd(&["--tasklists", "--set", "tasklists.enabled=true"], "- [x] a"));
+    assert!(html.contains(r#"checked="" data-line="0""#), "{}", html);
+
+    let html = stdout(wikimd(&["--smartypants", "--set", "smartypants.dashes=false"], "\"a\" -- b..."));
+    assert_eq!(html, "<p>“a” -- b…</p>\n");
+
+    let html = stdout(wikimd(&["--wikitables", "--set", "wikitables.alignment=false"], "||~ a ||  b  ||"));
+    assert!(html.contains("<th>a</th>\n<th>b</th>"), "{}", html);
+
+    let html = stdout(wikimd(&["--linkattrs", "--set", "linkattrs.internal_hosts=['a.org']"], "<https://a.org> <https://b.org>"));
+    assert!(html.contains(r#"<a href="https://a.org">"#) && html.contains(r#"<a class="external" target="_blank""#), "{}", html);
+
+    let html = stdout(wikimd(&["--markdown2", "--set", "toc.min_level=2"], "[TOC]\n\n## Café Menu"));
+    assert!(html.contains(r#"<h2 id="cafe-menu">"#), "{}", html);
+
+    let html = stdout(wikimd(&["--markdown2"], "~~a~~ $x$\n\n!!! note\n    a\n\n>! b"));
+    assert!(html.contains("<s>a</s>") && html.contains("<math") && html.contains("<aside") && html.contains("<details"), "{}", html);
+}
+
+#[test]
+fn options() {
+    let markdown = "[TOC]\n\n## A\n### B";
+    let html = stdout(wikimd(&["--set", "toc.min_level=3"], markdown));
+    assert!(!html.contains(r##"href="#a""##) && html.contains(r##"href="#b""##), "{}", html);
+
+    let dir = temp_dir("options");
+    let config = dir.join("wikimd.toml");
+    std::fs::write(&config, "[toc]\ntoc_class = \"contents\"\n").unwrap();
+    let html = stdout(wikimd(&["--config", config.to_str().unwrap()], markdown));
+    assert!(html.contains(r#"<nav class="contents">"#), "{}", html);
+
+    // --markdown2 numbers repeated ids like markdown2, even when other toc options are given.
+    let html = stdout(wikimd(&["--markdown2", "--set", "toc.min_headings=1"], "## A\n## A"));
+    assert!(html.contains(r#"<h2 id="a-2">"#), "{}", html);
+
+    let output = wikimd(&["--set", "tables.x=1"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown plugin `tables`"));
+}
+
+#[test]
+fn standalone() {
+    let html = stdout(wikimd(&["--standalone"], "# A & B\ntext"));
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert!(html.contains("<title>A &amp; B</title>"), "{}", html);
+
+    let dir = temp_dir("standalone");
+    let template = dir.join("page.html");
+    std::fs::write(&template, "{{title}}|{{toc}}|{{content}}").unwrap();
+    let html = stdout(wikimd(&["--standalone", "--template", template.to_str().unwrap()], "text"));
+    assert_eq!(html, "Untitled||<p>text</p>\n");
+
+    // Placeholders in the page itself aren't filled in.
+    let html = stdout(wikimd(&["--standalone", "--template", template.to_str().unwrap()], "# {{toc}}\n\n{{content}}"));
+    assert_eq!(html, "{{toc}}||<h1>{{toc}}</h1>\n<p>{{content}}</p>\n");
+
+    // The template places the table of contents, so the page doesn't get one as well, unless asked to.
+    let markdown = "[TOC]\n\n## A\n## B";
+    let html = stdout(wikimd(&["--standalone", "--template", template.to_str().unwrap(), "--markdown2"], markdown));
+    assert_eq!(html.matches("<nav").count(), 1, "{}", html);
+    assert!(html.contains(r#"|<nav class="table_of_contents">"#) && html.contains(r#"<h2 id="a">"#), "{}", html);
+    let args = ["--standalone", "--template", template.to_str().unwrap(), "--toc", "--set", "toc.inject=true"];
+    assert_eq!(stdout(wikimd(&args, markdown)).matches("<nav").count(), 2);
+}
+
+#[test]
+fn batch() {
+    let dir = temp_dir("batch");
+    std::fs::create_dir_all(dir.join("pages/nested")).unwrap();
+    std::fs::write(dir.join("pages/index.md"), "# Index").unwrap();
+    std::fs::write(dir.join("pages/nested/page.markdown"), "Page").unwrap();
+    std::fs::write(dir.join("pages/notes.txt"), "Not Markdown").unwrap();
+
+    let out = dir.join("out");
+    stdout(wikimd(&[dir.join("pages").to_str().unwrap(), "--out-dir", out.to_str().unwrap()], ""));
+    assert_eq!(std::fs::read_to_string(out.join("index.html")).unwrap(), "<h1>Index</h1>\n");
+    assert_eq!(std::fs::read_to_string(out.join("nested/page.html")).unwrap(), "<p>Page</p>\n");
+    assert!(!out.join("notes.html").exists());
+
+    let output = wikimd(&[dir.join("pages").to_str().unwrap()], "");
+    assert!(!output.status.success());
+
+    // Batch mode needs inputs, and can't name the output of stdin.
+    let output = wikimd(&["--out-dir", out.to_str().unwrap()], "");
+    assert!(!output.status.success());
+    let output = wikimd(&["-", "--out-dir", out.to_str().unwrap()], "text");
+    assert!(!output.status.success());
+    assert!(!out.join("-.html").exists());
+}
@@ -0,0 +1,52 @@
+use markdown_it::parser::inline::InlineRoot;
+
+/// Removes the first `length` bytes of the text of an inline root, and the whitespace after them, keeping the
+/// mapping of the rest of the text to the source right. For plugins which take a marker off the start of a
+/// paragraph before it's parsed.
+pub fn strip_start(inline: &mut InlineRoot, length: usize) {
+    let rest = &inline.content[length..];
+    let length = length + rest.len() - rest.trim_start().len();
+    inline.content.drain(..length);
+
+    let first = inline.mapping.iter().rposition(|(offset, _)| *offset <= length).unwrap_or(0);
+    let (offset, source) = inline.mapping[first];
+    inline.mapping.drain(..first);
+    inline.mapping[0] = (offset, source + length - offset);
+    for (offset, _) in inline.mapping.iter_mut() {
+        *offset = offset.saturating_sub(length);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stripped(content: &str, mapping: Vec<(usize, usize)>, length: usize) -> (String, Vec<(usize, usize)>) {
+        let mut inline = InlineRoot::new(content.to_string(), mapping);
+        strip_start(&mut inline, length);
+        (inline.content, inline.mapping)
+    }
+
+    #[test]
+    fn single_line() {
+        assert_eq!(stripped("[x]  a", vec![(0, 10)], 3), ("a".to_string(), vec![(0, 15)]));
+    }
+
+    #[test]
+    fn multi_line() {
+        // The lines of a paragraph in a list item, the second one indented in the source.
+        assert_eq!(stripped("[x] a\nb\nc", vec![(0, 10), (6, 20), (8, 30)], 3), (
+            "a\nb\nc".to_string(),
+            vec![(0, 14), (2, 20), (4, 30)]
+        ));
+    }
+
+    #[test]
+    fn across_lines() {
+        // The whitespace after the marker runs into the next line, so the first line is gone entirely.
+        assert_eq!(stripped("[!NOTE]\nb\nc", vec![(0, 10), (8, 20), (10, 30)], 7), (
+            "b\nc".to_string(),
+            vec![(0, 20), (2, 30)]
+        ));
+    }
+}
@@ -1,7 +1,7 @@
 use markdown_it::{
     MarkdownIt, Node, NodeValue, Renderer,
     parser::{
-        inline::{ InlineRule, InlineState },
+        inline::{ InlineRule, InlineState, builtin::InlineParserRule },
         block::{ BlockRule, BlockState },
         core::CoreRule,
         extset::MarkdownItExt
@@ -11,6 +11,7 @@ use markdown_it::{
 use std::collections::HashMap;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
 pub struct FootnoteOptions {
     fn_def_id_pref: String,
     fn_ref_id_pref: String,
@@ -108,6 +109,26 @@ impl NodeValue for FootnoteList {
     }
 }
 
+// Where the label of a reference or a definition, in `text` starting with `[^`, ends: at the first `]` which isn't
+// escaped. Without one, there's no label. Labels can't hold the start of anything else that could end after that
+// `]` (code, links, autolinks and HTML, or LaTeX), as markdown-it doesn't cope with tokens overlapping. `$` is
+// ruled out even when LaTeX isn't loaded: a rule can't tell which others are, and this way turning LaTeX on doesn't
+// change which footnotes a page has.
+fn label_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(2) {
+        if !escaped {
+            match c {
+                ']' => return Some(i),
+                '`' | '[' | '<' | '$' => return None,
+                _ => ()
+            }
+        }
+        escaped = !escaped && c == '\\';
+    }
+    None
+}
+
 struct FootnoteRefsInlinRule; // Finds references
 struct FootnoteDefsBlockRule; // Finds definitions
 struct FootnoteCountCoreRule; // Counts references per definition
@@ -116,19 +137,12 @@ struct FootnoteGroupCoreRule; // Groups adjacent definitions
 impl InlineRule for FootnoteRefsInlinRule {
     const MARKER: char = '[';
     fn run(state: &mut InlineState) -> Option<(Node, usize)> {
-        let input = &state.src[state.pos..];
-        if !input.starts_with("[^") || &state.src[state.pos-1..] == "\n" {
+        let input = &state.src[state.pos..state.pos_max];
+        if !input.starts_with("[^") {
             return None;
         }
-        
-        let mut last_pos = 0;
-        for i in 2..input.len() {
-            if !input[i..].starts_with("]") || input[i-1..].starts_with(r"\") {
-                continue;
-            }
-            last_pos = i;
-            break;
-        }
+
+        let last_pos = label_end(input)?;
         let label = String::from(&input[2..last_pos]);
 
         let options = state.md.ext.get::<FootnoteOptions>().unwrap();
@@ -151,23 +165,24 @@ impl InlineRule for FootnoteRefsInlinRule {
 }
 
 impl FootnoteDefsBlockRule {
-    fn get_label(state: &mut BlockState) -> Option<String> {
+    // The label, along with where the definition's contents start in the line (after `[^label]:` and a space).
+    fn get_label(state: &mut BlockState) -> Option<(String, usize)> {
         let line = state.get_line(state.line);
         if !line.starts_with("[^") { return None; }
 
-        let mut character = 2;
-        while character < line.len() {
-            if line[character..].starts_with("]") { break; }
-            character += 1;
-        }
-        let label = &line[2..character];
+        let end = label_end(line)?;
+        if !line[end + 1..].starts_with(':') { return None; }
+        let label = &line[2..end];
 
-        Some(label.to_string())
+        let mut contents = end + 2;
+        if line[contents..].starts_with(' ') { contents += 1; }
+
+        Some((label.to_string(), contents))
     }
 }
 impl BlockRule for FootnoteDefsBlockRule {
     fn run(state: &mut BlockState) -> Option<(Node, usize)> {
-        let label = Self::get_label(state)?;
+        let (label, contents) = Self::get_label(state)?;
 
         let options = state.md.ext.get::<FootnoteOptions>().unwrap();
 
@@ -183,8 +198,8 @@ impl BlockRule for FootnoteDefsBlockRule {
             id: label.clone(),
             count: 0,
 
-            br_text: br_text,
-            br_class: br_class,
+            br_text,
+            br_class,
 
             ref_id_prefix: ref_id_pref + "-" + &sluggify(&label) + "-"
         }); 
@@ -197,8 +212,8 @@ impl BlockRule for FootnoteDefsBlockRule {
         let init_line = state.line;
         let init_offsets = state.line_offsets[init_line].clone();
 
-        state.line_offsets[init_line].first_nonspace  += 5 + label.clone().len();
-        state.line_offsets[init_line].indent_nonspace += 5i32;
+        state.line_offsets[init_line].first_nonspace  += contents;
+        state.line_offsets[init_line].indent_nonspace += 4i32;
         
         state.blk_indent += 4;
         state.md.block.tokenize(state);
@@ -225,7 +240,7 @@ impl CoreRule for FootnoteCountCoreRule {
         root.walk_mut(|node, _| {
             let reference = match node.cast_mut::<FootnoteReference>() {
                 Some(r) => r,
-                None    => return ()
+                None    => return
             };
             let ref_id = reference.r#ref.clone();
             counts.entry(ref_id.clone()).and_modify(|c| *c += 1).or_insert(1);
@@ -237,7 +252,7 @@ impl CoreRule for FootnoteCountCoreRule {
         root.walk_mut(|node, _| {
             let definition = match node.cast_mut::<FootnoteDefinition>() {
                 Some(r) => r,
-                None    => return ()
+                None    => return
             };
             let def_id = definition.id.clone();
             counts.entry(def_id.clone()).or_insert(1);
@@ -255,33 +270,36 @@ impl NodeValue for PlaceholderNode {}
 impl CoreRule for FootnoteGroupCoreRule {
     fn run(root: &mut Node, md: &MarkdownIt) {
         let mut index = 0;
-        let mut open_index = 0;
+        let mut list_open = false;
         let options = md.ext.get::<FootnoteOptions>().unwrap();
         let class = options.fn_list_class.clone();
         let mut defs: Vec<(usize, Box<Node>)> = Vec::new();
         root.walk_mut(|node, _depth| {
             if !node.is::<FootnoteDefinition>() {
                 index += 1;
-                open_index = index;
+                list_open = false;
                 return;
             }
-            let mut replaced = if index != open_index { Node::new(PlaceholderNode()) } else { Node::new(FootnoteList(open_index)) };
+            // The first of adjacent definitions is replaced with their list, the others are removed.
+            let mut replaced = if list_open { Node::new(PlaceholderNode()) } else { Node::new(FootnoteList(index)) };
             replaced.attrs.push(("class", class.clone()));
+            list_open = true;
             let extract = std::mem::replace(node, replaced);
-            defs.push((open_index, Box::new(extract)));
+            defs.push((index, Box::new(extract)));
         });
-        index = 0;
-        root.children.retain(|child| {
-            if !child.is::<PlaceholderNode>() {
-                return true;
-            }
-            index += 1;
-            false
+        #[cfg(feature = "log")]
+        log::debug!(
+            "grouped {} footnote definitions into {} lists",
+            defs.len(),
+            defs.iter().map(|def| def.0).collect::<std::collections::HashSet<usize>>().len()
+        );
+        root.walk_mut(|node, _depth| {
+            node.children.retain(|child| !child.is::<PlaceholderNode>());
         });
         root.walk_mut(|node, _depth| {
             let def_list_id = match node.cast_mut::<FootnoteList>() {
                 Some(FootnoteList(n)) => *n,
-                None => return (),
+                None => return,
             };
             for def in &mut defs {
                 if def.0 != def_list_id { continue; }
@@ -296,7 +314,7 @@ pub fn add(md: &mut MarkdownIt) {
     md.ext.get_or_insert_default::<FootnoteOptions>();
     md.inline.add_rule::<FootnoteRefsInlinRule>();
     md.block.add_rule::<FootnoteDefsBlockRule>().before::<ReferenceScanner>();
-    md.add_rule::<FootnoteCountCoreRule>();
+    md.add_rule::<FootnoteCountCoreRule>().after::<InlineParserRule>();
     md.add_rule::<FootnoteGroupCoreRule>().after::<FootnoteCountCoreRule>();
 }
 
@@ -304,7 +322,7 @@ pub fn add_with_options(md: &mut MarkdownIt, options: FootnoteOptions) {
     md.ext.insert(options);
     md.inline.add_rule::<FootnoteRefsInlinRule>();
     md.block.add_rule::<FootnoteDefsBlockRule>().before::<ReferenceScanner>();
-    md.add_rule::<FootnoteCountCoreRule>();
+    md.add_rule::<FootnoteCountCoreRule>().after::<InlineParserRule>();
     md.add_rule::<FootnoteGroupCoreRule>().after::<FootnoteCountCoreRule>();
 }
 
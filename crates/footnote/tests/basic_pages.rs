@@ -1,13 +1,28 @@
 use rstest::*;
-use testing::Test;
+use testing::{ Test, Spec };
+use markdown_it_footnotes::FootnoteOptions;
 use std::path::PathBuf;
-use markdown_it_footnotes;
 
 #[rstest]
-fn main(#[files("tests/predone/test1-*.md")] path: PathBuf) {
-    let mut parser = Test::default_parser();
-    markdown_it_footnotes::add(&mut parser);
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
     let test = Test::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_footnotes::add_with_options(&mut parser, test.options::<FootnoteOptions>().unwrap());
     let matches = test.output_log(&parser);
     assert_eq!(matches.0, matches.1);
 }
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_footnotes::add_with_options(&mut parser, spec.options::<FootnoteOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let mut parser = Test::default_parser();
+    markdown_it_footnotes::add(&mut parser);
+    testing::assert_properties(&parser);
+}
@@ -10,6 +10,12 @@ pub fn latex_render_mathml(latex: &str, inline: bool) -> String {
     // Function powering the entire module. Just uses Pulldown-LaTeX.
     let store = Storage::new();
     let parse = Parser::new(latex, &store);
+    #[cfg(feature = "log")]
+    let parse = parse.inspect(|event| {
+        if let Err(e) = event {
+            log::warn!("failed to render formula `{}`: {}", latex, e);
+        }
+    });
 
     let mut mathml = String::new();
     match push_mathml(
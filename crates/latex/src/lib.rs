@@ -30,19 +30,23 @@ impl InlineRule for LaTeXScan {
         let latex_enter_count = if input.starts_with("$$") { 2 } else { 1 };
 
         let start_pos = state.pos + latex_enter_count;
-        let mut last_posi = state.pos + latex_enter_count;
-        let mut next_posi = state.pos + latex_enter_count;
+        let mut last_posi = start_pos;
+        let mut next_posi = start_pos;
 
         while next_posi < state.pos_max {
-            let current_input = &state.src[next_posi..];
+            let current_input = &state.src[next_posi..state.pos_max];
             if !current_input.starts_with(&"$".repeat(latex_enter_count)) {
-                next_posi += 1;
+                next_posi += current_input.chars().next().map_or(1, char::len_utf8);
                 continue;
             }
 
             last_posi = next_posi;
             break;
         }
+        // An unclosed formula is left as text.
+        if last_posi == start_pos && next_posi >= state.pos_max {
+            return None;
+        }
         let latex_text = &state.src[start_pos..last_posi];
         Some((
             Node::new(LaTeXNode {
@@ -1,10 +1,9 @@
 use rstest::*;
-use testing::Test;
+use testing::{ Test, Spec };
 use std::path::PathBuf;
-use markdown_it_latex;
 
 #[rstest]
-fn main(#[files("tests/predone/test1-*.md")] path: PathBuf) {
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
     println!("--{}--", path.display());
     let mut parser = Test::default_parser();
     markdown_it_latex::add(&mut parser);
@@ -12,3 +11,18 @@ fn main(#[files("tests/predone/test1-*.md")] path: PathBuf) {
     let matches = test.output_matches_log(&parser);
     assert!(matches);
 }
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_latex::add(&mut parser);
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let mut parser = Test::default_parser();
+    markdown_it_latex::add(&mut parser);
+    testing::assert_properties(&parser);
+}
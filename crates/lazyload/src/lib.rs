@@ -0,0 +1,120 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    parser::{
+        core::CoreRule,
+        extset::MarkdownItExt,
+        inline::{ Text, builtin::InlineParserRule }
+    },
+    plugins::cmark::inline::image::Image
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct LazyLoadOptions {
+    pub skip_first: usize, // the first images of a page, which are likely to be seen right away, are loaded as usual.
+    pub decoding: bool, // add decoding="async" as well.
+    pub dimensions: bool, // read the width and height of an image from {width=640 height=480} right after it.
+}
+
+impl Default for LazyLoadOptions {
+    fn default() -> Self {
+        Self {
+            skip_first: 0,
+            decoding: true,
+            dimensions: true
+        }
+    }
+}
+impl MarkdownItExt for LazyLoadOptions {}
+
+/// Width and height given to an image by following it with `{width=640 height=480}`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Dimensions {
+    width: Option<String>,
+    height: Option<String>
+}
+
+impl Dimensions {
+    // Splits the dimensions off the start of the text after an image. Like heading attributes, nothing
+    // is split off unless every attribute in the braces is known.
+    fn parse(content: &str) -> Option<(&str, Dimensions)> {
+        let inner = content.strip_prefix('{')?;
+        let close = inner.find('}')?;
+
+        let mut dimensions = Dimensions::default();
+        for attribute in inner[..close].split_whitespace() {
+            let (key, value) = attribute.split_once('=')?;
+            let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) { return None; }
+            let field = match key {
+                "width" => &mut dimensions.width,
+                "height" => &mut dimensions.height,
+                _ => return None
+            };
+            *field = Some(value.to_string());
+        }
+        if dimensions == Dimensions::default() { return None; }
+
+        Some((&inner[close + 1..], dimensions))
+    }
+}
+
+fn take_dimensions(node: &mut Node) {
+    let mut i = 0;
+    while i + 1 < node.children.len() {
+        i += 1;
+        if !node.children[i - 1].is::<Image>() { continue; }
+        let Some(text) = node.children[i].cast_mut::<Text>() else { continue };
+        let Some((rest, dimensions)) = Dimensions::parse(&text.content) else { continue };
+
+        text.content = rest.to_string();
+        if text.content.is_empty() {
+            node.children.remove(i);
+        }
+        let image = &mut node.children[i - 1];
+        if let Some(width) = dimensions.width {
+            image.attrs.push(("width", width));
+        }
+        if let Some(height) = dimensions.height {
+            image.attrs.push(("height", height));
+        }
+    }
+}
+
+fn has_attr(node: &Node, name: &str) -> bool {
+    node.attrs.iter().any(|(key, _)| *key == name)
+}
+
+pub struct LazyLoadRule;
+
+impl CoreRule for LazyLoadRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<LazyLoadOptions>().unwrap();
+        if options.dimensions {
+            root.walk_mut(|node, _| take_dimensions(node));
+        }
+
+        // Images are counted in the order they appear, including those in links and tables.
+        let mut count = 0;
+        root.walk_mut(|node, _| {
+            if !node.is::<Image>() { return; }
+            count += 1;
+            if count <= options.skip_first { return; }
+            if !has_attr(node, "loading") {
+                node.attrs.push(("loading", "lazy".to_string()));
+            }
+            if options.decoding && !has_attr(node, "decoding") {
+                node.attrs.push(("decoding", "async".to_string()));
+            }
+        });
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, LazyLoadOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: LazyLoadOptions) {
+    md.ext.insert(options);
+    md.add_rule::<LazyLoadRule>().after::<InlineParserRule>();
+}
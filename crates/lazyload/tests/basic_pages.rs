@@ -0,0 +1,28 @@
+use rstest::*;
+use testing::{ Test, Spec };
+use markdown_it_lazyload::LazyLoadOptions;
+use std::path::PathBuf;
+
+#[rstest]
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_lazyload::add_with_options(&mut parser, test.options::<LazyLoadOptions>().unwrap());
+    let matches = test.output_matches_log(&parser);
+    assert!(matches);
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_lazyload::add_with_options(&mut parser, spec.options::<LazyLoadOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let mut parser = Test::default_parser();
+    markdown_it_lazyload::add(&mut parser);
+    testing::assert_properties(&parser);
+}
@@ -0,0 +1,112 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    parser::{
+        core::CoreRule,
+        extset::MarkdownItExt,
+        inline::builtin::InlineParserRule
+    },
+    plugins::{
+        cmark::inline::{ autolink::Autolink, link::Link },
+        extra::linkify::Linkified
+    }
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct LinkAttributesOptions {
+    pub internal_hosts: Vec<String>, // hosts of the site itself, like "nekoweb.org". Relative links are always internal.
+    pub subdomains: bool, // subdomains of an internal host are internal as well.
+    // Added to external links; an empty one is left out.
+    pub target: String,
+    pub rel: String,
+    pub class: String
+}
+
+impl Default for LinkAttributesOptions {
+    fn default() -> Self {
+        Self {
+            internal_hosts: Vec::new(),
+            subdomains: true,
+            target: "_blank".to_string(),
+            rel: "noopener nofollow ugc".to_string(),
+            class: "external".to_string()
+        }
+    }
+}
+impl MarkdownItExt for LinkAttributesOptions {}
+
+impl LinkAttributesOptions {
+    /// Whether a link leads away from the site. Only http(s) links can: relative links, anchors, `mailto:` and the like
+    /// never do. An http(s) link whose host can't be told, like `http:example.com`, is taken to be external.
+    pub fn is_external(&self, url: &str) -> bool {
+        let rest = match url.split_once(':') {
+            _ if url.starts_with("//") => url,
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") => rest,
+            _ => return false
+        };
+        match host(rest) {
+            Some(host) => !self.internal_hosts.iter().any(|internal| {
+                let internal = internal.trim_end_matches('.').to_lowercase();
+                host == internal || (self.subdomains && host.strip_suffix(&internal).is_some_and(|sub| sub.ends_with('.')))
+            }),
+            None => true
+        }
+    }
+}
+
+// The lowercase host of the part of a URL after its scheme, without the user or the port. markdown-it has already
+// percent-encoded the URL, including any character of the host outside ASCII and the brackets around an IPv6 address.
+fn host(rest: &str) -> Option<String> {
+    let authority = rest.strip_prefix("//")?;
+    let authority = &authority[..authority.find(['/', '?', '#']).unwrap_or(authority.len())];
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap();
+    (!host.is_empty()).then(|| host.trim_end_matches('.').to_lowercase())
+}
+
+fn url(node: &Node) -> Option<&str> {
+    if let Some(link) = node.cast::<Link>() {
+        Some(&link.url)
+    } else if let Some(link) = node.cast::<Autolink>() {
+        Some(&link.url)
+    } else {
+        node.cast::<Linkified>().map(|link| link.url.as_str())
+    }
+}
+
+fn add_attr(node: &mut Node, name: &'static str, value: &str) {
+    if value.is_empty() { return; }
+    match node.attrs.iter_mut().find(|(key, _)| *key == name) {
+        // Classes are added to those the link already has, but other attributes which are already there are kept.
+        Some((_, classes)) if name == "class" => {
+            classes.push(' ');
+            classes.push_str(value);
+        },
+        Some(_) => (),
+        None => node.attrs.push((name, value.to_string()))
+    }
+}
+
+// Runs after inline parsing, when links have all been made, and leaves attributes other plugins gave a link alone.
+pub struct LinkAttributesRule;
+
+impl CoreRule for LinkAttributesRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<LinkAttributesOptions>().unwrap();
+        root.walk_mut(|node, _| {
+            if !url(node).is_some_and(|url| options.is_external(url)) { return; }
+            add_attr(node, "class", &options.class);
+            add_attr(node, "target", &options.target);
+            add_attr(node, "rel", &options.rel);
+        });
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, LinkAttributesOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: LinkAttributesOptions) {
+    md.ext.insert(options);
+    md.add_rule::<LinkAttributesRule>().after::<InlineParserRule>();
+}
@@ -0,0 +1,36 @@
+use rstest::*;
+use testing::{ Test, Spec };
+use markdown_it::MarkdownIt;
+use markdown_it_link_attributes::LinkAttributesOptions;
+use std::path::PathBuf;
+
+fn parser() -> MarkdownIt {
+    let mut parser = Test::default_parser();
+    markdown_it::plugins::extra::linkify::add(&mut parser);
+    parser
+}
+
+#[rstest]
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let mut parser = parser();
+    markdown_it_link_attributes::add_with_options(&mut parser, test.options::<LinkAttributesOptions>().unwrap());
+    let matches = test.output_matches_log(&parser);
+    assert!(matches);
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let mut parser = parser();
+    markdown_it_link_attributes::add_with_options(&mut parser, spec.options::<LinkAttributesOptions>().unwrap());
+    spec.run(&parser);
+}
+
+// Without linkify, which panics on some URLs after a tab in markdown-it 0.6.1 (`- a\n\t日本https://a.com`).
+#[test]
+fn properties() {
+    let mut parser = Test::default_parser();
+    markdown_it_link_attributes::add(&mut parser);
+    testing::assert_properties(&parser);
+}
@@ -0,0 +1,191 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    parser::{
+        core::CoreRule,
+        extset::MarkdownItExt,
+        inline::{ Text, TextSpecial, builtin::InlineParserRule }
+    },
+    plugins::{
+        cmark::inline::{
+            autolink::Autolink,
+            backticks::CodeInline,
+            emphasis::{ Em, Strong },
+            image::Image,
+            link::Link,
+            newline::{ Hardbreak, Softbreak }
+        },
+        extra::strikethrough::Strikethrough,
+        html::html_inline::HtmlInline
+    }
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct SmartyPantsOptions {
+    pub quotes: bool,
+    pub dashes: bool, // -- to an en dash and --- to an em dash, as in markdown2.
+    pub ellipses: bool, // ... and . . . to an ellipsis.
+    pub double_quotes: [String; 2], // opening and closing quote. Either can be more than one character, like "«\u{a0}".
+    pub single_quotes: [String; 2] // apostrophes are always ’, whichever quotes are used.
+}
+
+impl Default for SmartyPantsOptions {
+    fn default() -> Self {
+        Self::for_language("en").unwrap()
+    }
+}
+impl MarkdownItExt for SmartyPantsOptions {}
+
+impl SmartyPantsOptions {
+    /// The quotes used in a language: `en`, `de`, `de-ch`, `fr` or `ru`.
+    pub fn for_language(language: &str) -> Option<Self> {
+        let (double_quotes, single_quotes) = match language.to_lowercase().as_str() {
+            "en" => (["“", "”"], ["‘", "’"]),
+            "de" => (["„", "“"], ["‚", "‘"]),
+            "de-ch" => (["«", "»"], ["‹", "›"]),
+            "fr" => (["«\u{a0}", "\u{a0}»"], ["‹\u{a0}", "\u{a0}›"]),
+            "ru" => (["«", "»"], ["„", "“"]),
+            _ => return None
+        };
+        Some(Self {
+            quotes: true,
+            dashes: true,
+            ellipses: true,
+            double_quotes: double_quotes.map(String::from),
+            single_quotes: single_quotes.map(String::from)
+        })
+    }
+}
+
+const APOSTROPHE: &str = "’";
+// Stands in for code spans, maths and other nodes whose content is left alone, which count as a word before a quote.
+const OBJECT: char = '\u{fffc}';
+
+// Whether a quote after this character opens rather than closes. None is the start of a block, or an opening quote.
+fn opens_after(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| c.is_whitespace() || "([{<-–—/“‘«‹„‚".contains(c))
+}
+
+struct Educator<'a> {
+    options: &'a SmartyPantsOptions,
+    previous: Option<char>
+}
+
+impl Educator<'_> {
+    // Picks the replacement for a quote and whether it opens, or None to leave it straight. `next` is None at the end
+    // of a text node, where the next character can't be told.
+    fn quote(&self, quote: char, next: Option<char>) -> Option<(&str, bool)> {
+        let [open, close] = match quote {
+            '"' => &self.options.double_quotes,
+            _ => &self.options.single_quotes
+        };
+        let next_is_space = next.is_some_and(char::is_whitespace);
+        let alphanumeric = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+
+        if quote == '\'' && alphanumeric(self.previous) && alphanumeric(next) {
+            return Some((APOSTROPHE, false)); // don't
+        }
+        if !opens_after(self.previous) {
+            return Some((close, false));
+        }
+        if quote == '\'' && next.is_some_and(|c| c.is_ascii_digit()) {
+            return Some((APOSTROPHE, false)); // the '90s
+        }
+        (!next_is_space).then_some((open, true))
+    }
+
+    fn educate(&mut self, text: &str) -> String {
+        let options = self.options;
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let (replacement, length) = if options.dashes && rest.starts_with("---") {
+                ("—", 3)
+            } else if options.dashes && rest.starts_with("--") {
+                ("–", 2)
+            } else if options.ellipses && rest.starts_with("...") {
+                ("…", 3)
+            } else if options.ellipses && rest.starts_with(". . .") {
+                ("…", 5)
+            } else if options.quotes && (c == '"' || c == '\'') {
+                match self.quote(c, rest[1..].chars().next()) {
+                    Some((quote, opens)) => {
+                        result.push_str(quote);
+                        self.previous = if opens { None } else { Some('’') };
+                    },
+                    None => {
+                        result.push(c);
+                        self.previous = Some(c);
+                    }
+                }
+                rest = &rest[1..];
+                continue;
+            } else {
+                (&rest[..c.len_utf8()], c.len_utf8())
+            };
+            result.push_str(replacement);
+            self.previous = replacement.chars().last();
+            rest = &rest[length..];
+        }
+        result
+    }
+}
+
+// Runs right after inline parsing, so that plugins reading the text later on (like the table of contents) get the
+// curly quotes too.
+pub struct SmartyPantsRule;
+
+impl CoreRule for SmartyPantsRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<SmartyPantsOptions>().unwrap();
+        let mut educator = Educator { options, previous: None };
+        // Depth of the code span or autolink being skipped, if any.
+        let mut skip: Option<u32> = None;
+        // Depth of the image the node being visited might come right after.
+        let mut image: Option<u32> = None;
+
+        root.walk_mut(|node, depth| {
+            if skip.is_some_and(|skip| depth > skip) { return; }
+            skip = None;
+            let after_image = image == Some(depth);
+            if image.is_some_and(|image| depth <= image) { image = None; }
+            if node.is::<Image>() { image = Some(depth); }
+
+            if let Some(text) = node.cast_mut::<Text>() {
+                // Attributes right after an image, like markdown-it-lazyload's `{width="320"}`, are left as they are,
+                // whether or not the plugin reading them has run yet.
+                let attributes = match after_image && text.content.starts_with('{') {
+                    true => text.content.find('}').map_or(0, |close| close + 1),
+                    false => 0
+                };
+                if attributes > 0 { educator.previous = Some('}'); }
+                text.content = format!("{}{}", &text.content[..attributes], educator.educate(&text.content[attributes..]));
+            } else if let Some(special) = node.cast::<TextSpecial>() {
+                // Escaped characters and entities are left as they were written.
+                educator.previous = special.content.chars().last();
+            } else if node.is::<Softbreak>() || node.is::<Hardbreak>() {
+                educator.previous = Some('\n');
+            } else if node.is::<HtmlInline>() {
+                // Tags are looked through, so that a quote after <b> still opens.
+            } else if node.is::<CodeInline>() || node.is::<Autolink>() {
+                skip = Some(depth);
+                educator.previous = Some(OBJECT);
+            } else if node.children.is_empty() {
+                // Maths, code blocks and the like, which keep their content out of text nodes.
+                educator.previous = Some(OBJECT);
+            } else if !(node.is::<Em>() || node.is::<Strong>() || node.is::<Link>() || node.is::<Image>() || node.is::<Strikethrough>()) {
+                // Anything else with children is taken to be a block, where quotes start afresh.
+                educator.previous = None;
+            }
+        });
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, SmartyPantsOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: SmartyPantsOptions) {
+    md.ext.insert(options);
+    md.add_rule::<SmartyPantsRule>().after::<InlineParserRule>().before_all();
+}
@@ -0,0 +1,35 @@
+use rstest::*;
+use testing::{ Test, Spec };
+use markdown_it_smartypants::SmartyPantsOptions;
+use std::path::PathBuf;
+
+// Maths and raw HTML have to be left alone, so they're always there.
+fn parser(options: SmartyPantsOptions) -> markdown_it::MarkdownIt {
+    let mut parser = Test::default_parser();
+    markdown_it::plugins::html::add(&mut parser);
+    markdown_it::plugins::extra::strikethrough::add(&mut parser);
+    markdown_it_latex::add(&mut parser);
+    markdown_it_smartypants::add_with_options(&mut parser, options);
+    parser
+}
+
+#[rstest]
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let parser = parser(test.options::<SmartyPantsOptions>().unwrap());
+    let matches = test.output_matches_log(&parser);
+    assert!(matches);
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let parser = parser(spec.options::<SmartyPantsOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let parser = parser(SmartyPantsOptions::default());
+    testing::assert_properties(&parser);
+}
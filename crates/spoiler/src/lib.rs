@@ -0,0 +1,150 @@
+use markdown_it::{
+    MarkdownIt, Node, NodeValue, Renderer,
+    common::utils::find_indent_of,
+    generics::inline::emph_pair,
+    parser::{
+        block::{ BlockRule, BlockState },
+        core::CoreRule,
+        extset::MarkdownItExt,
+        inline::builtin::InlineParserRule
+    },
+    plugins::cmark::block::blockquote::BlockquoteScanner
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct SpoilerOptions {
+    pub class: String, // class of both the <details> of block spoilers and the <span> of inline ones.
+    pub summary: String, // text of the <summary> a block spoiler is opened with.
+    pub blocks: bool, // >! blocks, as in markdown2.
+    pub inline: bool // ||inline|| spoilers.
+}
+
+impl Default for SpoilerOptions {
+    fn default() -> Self {
+        Self {
+            class: "spoiler".to_string(),
+            summary: "Spoiler".to_string(),
+            blocks: true,
+            inline: true
+        }
+    }
+}
+impl MarkdownItExt for SpoilerOptions {}
+
+/// Block spoiler, whose contents are its children.
+#[derive(Debug)]
+pub struct BlockSpoiler {
+    pub summary: String
+}
+
+impl NodeValue for BlockSpoiler {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("details", &node.attrs);
+        fmt.cr();
+        fmt.open("summary", &[]);
+        fmt.text(&self.summary);
+        fmt.close("summary");
+        fmt.cr();
+        fmt.contents(&node.children);
+        fmt.cr();
+        fmt.close("details");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug)]
+pub struct InlineSpoiler;
+
+impl NodeValue for InlineSpoiler {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("span", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("span");
+    }
+}
+
+// Every line of a block spoiler starts with `>!`, which is taken off the same way blockquotes take off
+// their `>`, before its contents are parsed.
+struct BlockSpoilerScanner;
+
+impl BlockSpoilerScanner {
+    fn is_spoiler(state: &BlockState, line: usize) -> bool {
+        state.line_indent(line) >= 0 && state.line_indent(line) < 4 && state.get_line(line).starts_with(">!")
+    }
+}
+
+impl BlockRule for BlockSpoilerScanner {
+    fn check(state: &mut BlockState) -> Option<()> {
+        Self::is_spoiler(state, state.line).then_some(())
+    }
+
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        Self::check(state)?;
+        let options = state.md.ext.get::<SpoilerOptions>().unwrap();
+        let mut node = Node::new(BlockSpoiler { summary: options.summary.clone() });
+        node.attrs.push(("class", options.class.clone()));
+
+        let start = state.line;
+        let mut end = start;
+        let mut old_line_offsets = Vec::new();
+        while end < state.line_max && Self::is_spoiler(state, end) {
+            let offsets = &state.line_offsets[end];
+            let line = &state.src[offsets.line_start..offsets.line_end];
+            let mut after_marker = offsets.first_nonspace + 2 - offsets.line_start;
+            // One space after the marker is part of it.
+            if line[after_marker..].starts_with(' ') { after_marker += 1; }
+            let (indent, first_nonspace) = find_indent_of(line, after_marker);
+
+            old_line_offsets.push(offsets.clone());
+            state.line_offsets[end].indent_nonspace = indent as i32;
+            state.line_offsets[end].first_nonspace = state.line_offsets[end].line_start + first_nonspace;
+            end += 1;
+        }
+
+        let old_node = std::mem::replace(&mut state.node, node);
+        let old_indent = state.blk_indent;
+        let old_line_max = state.line_max;
+        state.blk_indent = 0;
+        state.line_max = end;
+        state.md.block.tokenize(state);
+        state.line = start;
+        state.line_max = old_line_max;
+        state.blk_indent = old_indent;
+        for (i, offsets) in old_line_offsets.into_iter().enumerate() {
+            state.line_offsets[start + i] = offsets;
+        }
+
+        Some((std::mem::replace(&mut state.node, old_node), end - start))
+    }
+}
+
+// emph_pair makes the nodes without the options, so they're given their class afterwards.
+struct InlineSpoilerClassRule;
+
+impl CoreRule for InlineSpoilerClassRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<SpoilerOptions>().unwrap();
+        root.walk_mut(|node, _| {
+            if node.is::<InlineSpoiler>() {
+                node.attrs.push(("class", options.class.clone()));
+            }
+        });
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, SpoilerOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: SpoilerOptions) {
+    if options.blocks {
+        md.block.add_rule::<BlockSpoilerScanner>().before::<BlockquoteScanner>();
+    }
+    if options.inline {
+        emph_pair::add_with::<'|', 2, true>(md, || Node::new(InlineSpoiler));
+        md.add_rule::<InlineSpoilerClassRule>().after::<InlineParserRule>();
+    }
+    md.ext.insert(options);
+}
@@ -0,0 +1,34 @@
+use rstest::*;
+use testing::{ Test, Spec };
+use markdown_it_spoiler::SpoilerOptions;
+use std::path::PathBuf;
+
+// Spoilers are often wrapped around footnotes and maths, so those are always there.
+fn parser(options: SpoilerOptions) -> markdown_it::MarkdownIt {
+    let mut parser = Test::default_parser();
+    markdown_it_latex::add(&mut parser);
+    markdown_it_footnotes::add(&mut parser);
+    markdown_it_spoiler::add_with_options(&mut parser, options);
+    parser
+}
+
+#[rstest]
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let parser = parser(test.options::<SpoilerOptions>().unwrap());
+    let matches = test.output_matches_log(&parser);
+    assert!(matches);
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let parser = parser(spec.options::<SpoilerOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let parser = parser(SpoilerOptions::default());
+    testing::assert_properties(&parser);
+}
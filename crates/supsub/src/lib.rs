@@ -0,0 +1,154 @@
+use markdown_it::{
+    MarkdownIt, Node, NodeValue, Renderer,
+    parser::{
+        extset::MarkdownItExt,
+        inline::{ InlineRule, InlineState, Text, TextSpecial }
+    }
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct SupSubOptions {
+    pub superscript: bool, // ^superscript^
+    pub subscript: bool, // ~subscript~
+    pub shorthand: bool, // markdown2's super^script, which lasts until the end of the word. Needs superscript.
+}
+
+impl Default for SupSubOptions {
+    fn default() -> Self {
+        Self {
+            superscript: true,
+            subscript: true,
+            shorthand: true
+        }
+    }
+}
+impl MarkdownItExt for SupSubOptions {}
+
+#[derive(Debug)]
+pub struct Superscript;
+
+impl NodeValue for Superscript {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("sup", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("sup");
+    }
+}
+
+#[derive(Debug)]
+pub struct Subscript;
+
+impl NodeValue for Subscript {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("sub", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("sub");
+    }
+}
+
+fn previous_char(state: &InlineState) -> Option<char> {
+    state.src[..state.pos].chars().next_back()
+}
+
+// Finds the closing marker of `^text^` or `~text~`, which Pandoc doesn't allow whitespace between other
+// than escaped spaces. Returns where the contents end. Contents can't hold a `[` either: markdown-it
+// caches where link labels end regardless of where the contents stop, so a link could end past the
+// closing marker.
+fn find_closing(state: &InlineState, marker: char) -> Option<usize> {
+    let start = state.pos + 1;
+    let mut chars = state.src[start..state.pos_max].char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            // Skips the escaped character either way.
+            '\\' if chars.next().is_some_and(|(_, c)| c.is_whitespace() && c != ' ') => return None,
+            '[' => return None,
+            c if c.is_whitespace() => return None,
+            c if c == marker => return if offset == 0 { None } else { Some(start + offset) },
+            _ => ()
+        }
+    }
+    None
+}
+
+// Parses the contents between the markers into `node`, the same way links parse their text.
+fn nested(state: &mut InlineState, node: Node, end: usize) -> Node {
+    let old_node = std::mem::replace(&mut state.node, node);
+    let (start, max) = (state.pos, state.pos_max);
+    state.pos = start + 1;
+    state.pos_max = end;
+    state.md.inline.tokenize(state);
+    state.pos = start;
+    state.pos_max = max;
+
+    // Escaped spaces become non-breaking ones, as in Pandoc, instead of keeping their backslash.
+    let mut node = std::mem::replace(&mut state.node, old_node);
+    node.walk_mut(|node, _| {
+        if let Some(special) = node.cast_mut::<TextSpecial>() {
+            if special.markup == "\\ " {
+                special.content = "\u{a0}".to_string();
+            }
+        }
+    });
+    node
+}
+
+struct SuperscriptScanner;
+
+impl InlineRule for SuperscriptScanner {
+    const MARKER: char = '^';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        if !state.src[state.pos..state.pos_max].starts_with('^') { return None; }
+        // [^ is a footnote, whether or not the footnote plugin is there to pick it up.
+        let previous = previous_char(state);
+        if previous == Some('[') { return None; }
+
+        let options = state.md.ext.get::<SupSubOptions>().unwrap();
+        if let Some(end) = find_closing(state, '^') {
+            let node = nested(state, Node::new(Superscript), end);
+            return Some((node, end + 1 - state.pos));
+        }
+
+        if !options.shorthand || previous.is_none_or(|c| c.is_whitespace() || c == '^') { return None; }
+        let word = &state.src[state.pos + 1..state.pos_max];
+        let length = word.find(|c: char| !c.is_alphanumeric()).unwrap_or(word.len());
+        if length == 0 { return None; }
+
+        let mut node = Node::new(Superscript);
+        let mut text = Node::new(Text { content: word[..length].to_string() });
+        text.srcmap = state.get_map(state.pos + 1, state.pos + 1 + length);
+        node.children.push(text);
+        Some((node, length + 1))
+    }
+}
+
+struct SubscriptScanner;
+
+impl InlineRule for SubscriptScanner {
+    const MARKER: char = '~';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        // ~~ is strikethrough, and so is any longer run of tildes.
+        if !input.starts_with('~') || input.starts_with("~~") || previous_char(state) == Some('~') { return None; }
+
+        let end = find_closing(state, '~')?;
+        if state.src[end + 1..state.pos_max].starts_with('~') { return None; }
+        let node = nested(state, Node::new(Subscript), end);
+        Some((node, end + 1 - state.pos))
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, SupSubOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: SupSubOptions) {
+    // Both markers have to be looked at before strikethrough, which takes every run of tildes as a delimiter.
+    if options.superscript {
+        md.inline.add_rule::<SuperscriptScanner>().before_all();
+    }
+    if options.subscript {
+        md.inline.add_rule::<SubscriptScanner>().before_all();
+    }
+    md.ext.insert(options);
+}
@@ -0,0 +1,33 @@
+use rstest::*;
+use testing::{ Test, Spec };
+use markdown_it_supsub::SupSubOptions;
+use std::path::PathBuf;
+
+// Strikethrough and footnotes share markers with subscript and superscript, so they're always there.
+fn parser(options: SupSubOptions) -> markdown_it::MarkdownIt {
+    let mut parser = Test::default_parser();
+    markdown_it::plugins::extra::strikethrough::add(&mut parser);
+    markdown_it_footnotes::add(&mut parser);
+    markdown_it_supsub::add_with_options(&mut parser, options);
+    parser
+}
+
+#[rstest]
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let parser = parser(test.options::<SupSubOptions>().unwrap());
+    let matches = test.output_matches_log(&parser);
+    assert!(matches);
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let parser = parser(spec.options::<SupSubOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    testing::assert_properties(&parser(SupSubOptions::default()));
+}
@@ -0,0 +1,135 @@
+use markdown_it::{
+    MarkdownIt, Node, NodeValue, Renderer,
+    common::sourcemap::SourcePos,
+    parser::{
+        block::builtin::BlockParserRule,
+        core::{ CoreRule, Root },
+        extset::MarkdownItExt,
+        inline::{ InlineRoot, builtin::InlineParserRule }
+    },
+    plugins::cmark::block::{
+        list::{ BulletList, ListItem, OrderedList },
+        paragraph::Paragraph
+    }
+};
+use wikimd_common::strip_start;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct TaskListOptions {
+    pub item_class: String, // class of the <li> of a task.
+    pub list_class: String, // class of a list with at least one task in it.
+    pub checkbox_class: String,
+    pub enabled: bool // checkboxes can be clicked, and carry the line of their task in data-line, so that an editor
+                      // can toggle the task in the source.
+}
+
+impl Default for TaskListOptions {
+    fn default() -> Self {
+        Self {
+            item_class: "task-list-item".to_string(),
+            list_class: "contains-task-list".to_string(),
+            checkbox_class: "task-list-item-checkbox".to_string(),
+            enabled: false
+        }
+    }
+}
+impl MarkdownItExt for TaskListOptions {}
+
+/// Checkbox at the start of a task.
+#[derive(Debug)]
+pub struct TaskCheckbox {
+    pub checked: bool,
+    pub line: Option<usize>, // index of the line the task starts on, counting from 0. Only set when the checkbox is
+                             // enabled.
+}
+
+impl NodeValue for TaskCheckbox {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = vec![("type", "checkbox".to_string())];
+        attrs.extend(node.attrs.iter().cloned());
+        if self.checked {
+            attrs.push(("checked", String::new()));
+        }
+        match self.line {
+            Some(line) => attrs.push(("data-line", line.to_string())),
+            None => attrs.push(("disabled", String::new()))
+        }
+        fmt.self_close("input", &attrs);
+        // The space after the marker was taken off along with it, so the text doesn't start with a line break.
+        fmt.text(" ");
+    }
+}
+
+// Returns whether the task is done, and the length of its marker. Like markdown2 and GitHub, the marker has to be
+// followed by whitespace.
+fn marker(content: &str) -> Option<(bool, usize)> {
+    let checked = match content.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None
+    };
+    content[3..].starts_with(char::is_whitespace).then_some((checked, 3))
+}
+
+// Puts a checkbox before the text of a list item if it starts with a marker. The text is straight in the item in
+// tight lists, and in its first paragraph otherwise.
+fn task(item: &mut Node, options: &TaskListOptions, line_starts: &[usize]) -> bool {
+    let parent = match item.children.first().is_some_and(|child| child.is::<Paragraph>()) {
+        true => &mut item.children[0],
+        false => &mut *item
+    };
+    let Some(inline) = parent.children.first_mut().and_then(|child| child.cast_mut::<InlineRoot>()) else { return false };
+    let Some((checked, length)) = marker(&inline.content) else { return false };
+
+    let start = inline.mapping[0].1;
+    strip_start(inline, length);
+    let line = options.enabled.then(|| line_starts.partition_point(|&line_start| line_start <= start) - 1);
+    let mut checkbox = Node::new(TaskCheckbox { checked, line });
+    checkbox.srcmap = Some(SourcePos::new(start, start + length));
+    if !options.checkbox_class.is_empty() {
+        checkbox.attrs.push(("class", options.checkbox_class.clone()));
+    }
+    parent.children.insert(0, checkbox);
+
+    if !options.item_class.is_empty() {
+        item.attrs.push(("class", options.item_class.clone()));
+    }
+    true
+}
+
+// Runs between block and inline parsing, like GitHub's alerts, so that `[x]` can't become a link first.
+pub struct TaskListRule;
+
+impl CoreRule for TaskListRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let options = md.ext.get::<TaskListOptions>().unwrap();
+        let line_starts: Vec<usize> = match options.enabled {
+            true => {
+                let source = &root.cast::<Root>().unwrap().content;
+                std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1)).collect()
+            },
+            false => Vec::new()
+        };
+
+        root.walk_mut(|node, _| {
+            if !node.is::<BulletList>() && !node.is::<OrderedList>() { return; }
+            let mut tasks = false;
+            for item in node.children.iter_mut().filter(|child| child.is::<ListItem>()) {
+                tasks |= task(item, options, &line_starts);
+            }
+            if tasks && !options.list_class.is_empty() {
+                node.attrs.push(("class", options.list_class.clone()));
+            }
+        });
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, TaskListOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: TaskListOptions) {
+    md.ext.insert(options);
+    md.add_rule::<TaskListRule>().after::<BlockParserRule>().before::<InlineParserRule>();
+}
@@ -0,0 +1,88 @@
+use similar::TextDiff;
+
+// Set to rewrite the expected output of failing fixtures with what the plugins produce instead.
+pub(crate) const BLESS_VAR: &str = "BLESS";
+
+pub(crate) fn blessing() -> bool {
+    std::env::var(BLESS_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Unified diff between the expected and the actual output, line by line.
+pub fn diff(expected: &str, actual: &str) -> String {
+    TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .context_radius(3)
+        .header("expected", "actual")
+        .to_string()
+}
+
+// Elements browsers lay out on lines of their own, so whitespace next to their tags isn't rendered.
+const BLOCK_TAGS: &[&str] = &[
+    "html", "head", "body", "title", "meta", "link", "style", "script", "address", "article", "aside", "blockquote",
+    "br", "details", "summary", "dd", "div", "dl", "dt", "figcaption", "figure", "footer", "header", "h1", "h2", "h3",
+    "h4", "h5", "h6", "hr", "li", "main", "nav", "ol", "p", "pre", "section", "table", "thead", "tbody", "tfoot", "tr",
+    "th", "td", "ul"
+];
+
+fn is_block_tag(piece: &str) -> bool {
+    let name = piece.trim_start_matches(['<', '/'])
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default();
+    piece.starts_with('<') && BLOCK_TAGS.iter().any(|tag| tag.eq_ignore_ascii_case(name))
+}
+
+/// Collapses runs of whitespace and drops any whitespace next to the tags of block-level elements, so HTML
+/// which only differs in its formatting compares equal. Whitespace next to inline tags is kept, since it's
+/// rendered. Every tag is put on its own line, to keep diffs of the result readable.
+pub fn normalize_html(html: &str) -> String {
+    let collapsed = html.split_whitespace().collect::<Vec<&str>>().join(" ");
+    // Tags and the text between them.
+    let mut pieces = Vec::new();
+    let mut rest = collapsed.as_str();
+    while !rest.is_empty() {
+        let end = match rest.starts_with('<') {
+            true => rest.find('>').map_or(rest.len(), |close| close + 1),
+            false => rest.find('<').unwrap_or(rest.len())
+        };
+        pieces.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    let mut normalized = String::with_capacity(collapsed.len());
+    for (i, &piece) in pieces.iter().enumerate() {
+        let mut piece = piece;
+        if !piece.starts_with('<') {
+            if i == 0 || is_block_tag(pieces[i - 1]) { piece = piece.trim_start(); }
+            if i + 1 == pieces.len() || is_block_tag(pieces[i + 1]) { piece = piece.trim_end(); }
+        }
+        if piece.is_empty() { continue; }
+        if piece.starts_with('<') && normalized.ends_with('>') {
+            normalized.push('\n');
+        }
+        normalized.push_str(piece);
+    }
+    normalized + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizing() {
+        assert_eq!(
+            normalize_html("<ol>\n  <li>Lorem   ipsum</li>\n</ol>"),
+            normalize_html("<ol><li>Lorem ipsum</li></ol>\n")
+        );
+        assert_ne!(normalize_html("<p>Lorem ipsum</p>"), normalize_html("<p>Loremipsum</p>"));
+        assert_ne!(normalize_html("<p>Lorem <em>ipsum</em></p>"), normalize_html("<p>Lorem<em>ipsum</em></p>"));
+        assert_eq!(normalize_html("<p>Lorem <em>ipsum</em> </p>"), "<p>Lorem <em>ipsum</em>\n</p>\n");
+    }
+
+    #[test]
+    fn diffing() {
+        let diff = diff("<h1>Lorem</h1>\n<p>ipsum</p>\n", "<h2>Lorem</h2>\n<p>ipsum</p>\n");
+        assert!(diff.contains("-<h1>Lorem</h1>\n+<h2>Lorem</h2>\n <p>ipsum</p>\n"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::panic::{ catch_unwind, resume_unwind, AssertUnwindSafe };
+use markdown_it::MarkdownIt;
+use proptest::prelude::*;
+use proptest::test_runner::{ Config, TestRunner, TestCaseError };
+use crate::Test;
+
+// Pieces of the syntax the plugins look for, along with the characters which tend to trip up byte
+// offsets. Raw HTML is left out, since it's passed through without being checked.
+const FRAGMENTS: &[&str] = &[
+    "[^", "]", "]: ", "[^note]", "[^日本]", "\\", "$", "$$", "x^2", "\\frac{a}{b}", "\\alpha_{2}^{4}",
+    "{", "}", "{.no-toc}", "{.unnumbered}", "{#id}", "{toc=\"a\"}", "[TOC]", "[TOC:", "[NOTOC]", "[[_TOC_]]",
+    "# ", "## ", "### ", "\n", "\n\n", " ", "    ", "\t", "> ", "- ", "1. ", "* ", "`", "```",
+    "~~", "~", "^", "H~2~O", "^a^", "![a](b.png)", "{width=10}", "!!! note", " \"Title\"", "[!NOTE]", "> [!TIP]\n",
+    ">!", ">! ", "||", "||a||", "- [ ] ", "- [x] ", "[X]", "--", "---", "...", ". . .", "'s",
+    "|| ", " ||", "||~", "\\||",
+    "https://a.com", "](//c.net)", "[a](http:", "user@", "nekoweb.org",
+    "_", "*", "[", "(", ")", "!", ":", "|", "-", "=", "&", "\"", "'", "é", "日本語", "🐱", "lorem", "ipsum"
+];
+
+/// Markdown made up of the syntax the plugins handle, mixed with arbitrary characters (other than `<`).
+pub fn arbitrary_markdown() -> impl Strategy<Value = String> {
+    let piece = prop_oneof![
+        3 => prop::sample::select(FRAGMENTS).prop_map(String::from),
+        1 => any::<char>().prop_filter("raw HTML", |c| *c != '<').prop_map(String::from)
+    ];
+    prop::collection::vec(piece, 0..64).prop_map(|pieces| pieces.concat())
+}
+
+// Elements which don't have a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr"
+];
+
+/// Checks that every tag is closed in the right order, attributes are quoted properly, and that there
+/// are no stray `<`.
+pub fn well_formed(html: &str) -> Result<(), String> {
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or("unclosed comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+
+        let closing = rest.starts_with("</");
+        let tag = &rest[if closing { 2 } else { 1 }..];
+        let name_len = tag.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':')).unwrap_or(tag.len());
+        let name = &tag[..name_len];
+        if name.is_empty() {
+            return Err(format!("unescaped < at {:?}", truncate(rest)));
+        }
+
+        // Attributes, skipping over anything in quotes.
+        let mut quote = None;
+        let mut end = None;
+        for (i, c) in tag[name_len..].char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {},
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '<') => return Err(format!("< inside of the tag {:?}", truncate(rest))),
+                (None, '>') => { end = Some(name_len + i); break; },
+                _ => {}
+            }
+        }
+        let end = end.ok_or_else(|| format!("unclosed tag {:?}", truncate(rest)))?;
+        let self_closing = tag[..end].ends_with('/');
+        rest = &tag[end + 1..];
+
+        if closing {
+            match open.pop() {
+                Some(opened) if opened == name => {},
+                Some(opened) => return Err(format!("</{}> closes <{}>", name, opened)),
+                None => return Err(format!("</{}> was never opened", name))
+            }
+        } else if !self_closing && !VOID_ELEMENTS.contains(&name) {
+            open.push(name);
+        }
+    }
+    match open.last() {
+        Some(name) => Err(format!("<{}> is never closed", name)),
+        None => Ok(())
+    }
+}
+
+fn truncate(html: &str) -> &str {
+    html.char_indices().nth(40).map_or(html, |(i, _)| &html[..i])
+}
+
+/// Renders `input`, checking the output is well-formed unless the input contains raw HTML. Meant for
+/// fuzz targets, which are expected to panic on failure.
+pub fn check(md: &MarkdownIt, input: &str) -> Result<String, String> {
+    let html = md.parse(input).render();
+    if !input.contains('<') {
+        well_formed(&html)?;
+    }
+    Ok(html)
+}
+
+/// Renders a few hundred `arbitrary_markdown` inputs, panicking with the smallest input which made the
+/// parser panic or produced output that isn't well-formed. Set `PROPTEST_CASES` to run more.
+///
+/// Inputs which also make markdown-it panic without any plugins are skipped, as that isn't for the plugins to fix.
+pub fn assert_properties(md: &MarkdownIt) {
+    let mut runner = TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&arbitrary_markdown(), |input| {
+        match catch_unwind(AssertUnwindSafe(|| check(md, &input))) {
+            Ok(checked) => checked.map(|_| ()).map_err(TestCaseError::fail),
+            Err(_) if catch_unwind(|| Test::default_parser().parse(&input).render()).is_err() => {
+                Err(TestCaseError::reject("markdown-it panics without any plugins"))
+            },
+            Err(panic) => resume_unwind(panic)
+        }
+    });
+    if let Err(error) = result {
+        panic!("{}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_formedness() {
+        assert_eq!(well_formed("<p>Lorem<br>ipsum <img src=\"a>b\" /></p>\n<!-- <p> -->"), Ok(()));
+        assert!(well_formed("<p><em>Lorem</p></em>").is_err());
+        assert!(well_formed("<p>Lorem").is_err());
+        assert!(well_formed("<p>1 < 2</p>").is_err());
+        assert!(well_formed("<a href=\"#\">Lorem</a").is_err());
+    }
+}
@@ -1,42 +1,86 @@
-use std::path::Path;
+mod compare;
+mod fuzz;
+mod spec;
+pub use crate::compare::{ diff, normalize_html };
+pub use crate::fuzz::{ arbitrary_markdown, assert_properties, check, well_formed };
+pub use crate::spec::{ Spec, Example };
+
+use std::path::{ Path, PathBuf };
 use markdown_it::MarkdownIt;
+use serde::de::DeserializeOwned;
 use std::convert::AsRef;
 
+// Surrounds the optional TOML header holding the plugin options for a test.
+pub(crate) const OPTIONS_DELIMITER: &str = "+++";
+
 pub struct Test {
     input: String,
     expected: String,
-    split: String
+    split: String,
+    options: Option<String>,
+    path: Option<PathBuf>, // file the test was read from, which is rewritten when blessing.
+    ignore_whitespace: bool
 }
 
 impl Test {
     pub fn from(predone: &str) -> Self {
         let mut lines = predone.lines();
-        let splitting = lines.next().unwrap();
+        let mut splitting = lines.next().unwrap();
+        let mut options = None;
+        if splitting == OPTIONS_DELIMITER {
+            let mut header = String::new();
+            for line in lines.by_ref() {
+                if line == OPTIONS_DELIMITER {
+                    break;
+                }
+                header.push_str(&(line.to_string() + "\n"));
+            }
+            options = Some(header);
+            splitting = lines.next().unwrap();
+        }
         let mut input = String::new();
         let mut expect = String::new();
-        while let Some(line) = lines.next() {
+        for line in lines.by_ref() {
             if line == splitting {
-                println!("line is splitting: {}", line);
                 break;
             }
             let final_line = line.to_string() + "\n";
             input.push_str(&final_line);
         }
-        while let Some(line) = lines.next() {
+        for line in lines {
             let final_line = line.to_string() + "\n";
             expect.push_str(&final_line);
         }
 
         
         Test {
-            input: input,
+            input,
             expected: expect,
-            split: splitting.to_string()
+            split: splitting.to_string(),
+            options,
+            path: None,
+            ignore_whitespace: false
         }
     }
     pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
-        let contents = std::fs::read_to_string(path);
-        Ok(Test::from(&contents?))
+        let contents = std::fs::read_to_string(&path);
+        Ok(Test {
+            path: Some(path.as_ref().to_path_buf()),
+            ..Test::from(&contents?)
+        })
+    }
+
+    /// Compares the output after `normalize_html`, for tests which only care about the markup and not
+    /// about how it's laid out.
+    pub fn ignoring_whitespace(mut self) -> Self {
+        self.ignore_whitespace = true;
+        self
+    }
+
+    /// Plugin options from the test's header; fields the header leaves out (or all of them, if the test
+    /// has no header) are expected to be filled in by `#[serde(default)]`.
+    pub fn options<T: DeserializeOwned>(&self) -> Result<T, toml::de::Error> {
+        toml::from_str(self.options.as_deref().unwrap_or(""))
     }
 
     pub fn default_parser() -> MarkdownIt {
@@ -45,14 +89,49 @@ impl Test {
         parser
     }
 
+    fn compared(&self, output: &str) -> String {
+        if self.ignore_whitespace { normalize_html(output) } else { output.to_string() }
+    }
+
     pub fn output_matches(&self, md: &MarkdownIt) -> bool {
-        md.parse(&self.input).render() == self.expected
+        let actual_output = md.parse(&self.input).render();
+        self.compared(&actual_output) == self.compared(&self.expected)
     }
+    /// Like `output_matches`, but prints a diff when the output doesn't match. With the `BLESS` environment
+    /// variable set, the expected output of a test read with `from_file` is replaced with the actual one.
     pub fn output_matches_log(&self, md: &MarkdownIt) -> bool {
         let actual_output = md.parse(&self.input).render();
-        let matches = actual_output == self.expected;
-        println!("EXPECTED\n---\n{}\n---\nACTUAL\n---\n{}\n---\nMATCHES? {}", self.expected, actual_output, matches);
-        matches
+        let (actual, expected) = (self.compared(&actual_output), self.compared(&self.expected));
+        if actual == expected {
+            return true;
+        }
+        if let (true, Some(path)) = (compare::blessing(), &self.path) {
+            self.bless(path, &actual_output).unwrap();
+            println!("blessed {}", path.display());
+            return true;
+        }
+        println!("{}", diff(&expected, &actual));
+        false
+    }
+
+    // Keeps everything up to the second delimiter as it is, replacing only what comes after it.
+    fn bless(&self, path: &Path, actual_output: &str) -> std::io::Result<()> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut kept = String::new();
+        let mut delimiters = 0;
+        let mut in_options = false;
+        for (index, line) in contents.lines().enumerate() {
+            kept.push_str(&(line.to_string() + "\n"));
+            if index == 0 && line == OPTIONS_DELIMITER {
+                in_options = true;
+            } else if in_options {
+                in_options = line != OPTIONS_DELIMITER;
+            } else if line == self.split {
+                delimiters += 1;
+                if delimiters == 2 { break; }
+            }
+        }
+        std::fs::write(path, kept + actual_output)
     }
 
     pub fn output(&self, md: &MarkdownIt) -> (String, String) {
@@ -60,8 +139,15 @@ impl Test {
     }
     pub fn output_log(&self, md: &MarkdownIt) -> (String, String) {
         let actual_output = md.parse(&self.input).render();
-        let matches = self.expected == actual_output;
-        println!("EXPECTED\n---\n{}\n---\nACTUAL\n---\n{}\n---\nMATCHES? {}", self.expected, actual_output, matches);
+        if self.compared(&actual_output) == self.compared(&self.expected) {
+            return (actual_output, self.expected.clone());
+        }
+        if let (true, Some(path)) = (compare::blessing(), &self.path) {
+            self.bless(path, &actual_output).unwrap();
+            println!("blessed {}", path.display());
+            return (actual_output.clone(), actual_output);
+        }
+        println!("{}", diff(&self.compared(&self.expected), &self.compared(&actual_output)));
         (actual_output, self.expected.clone())
     }
 
@@ -92,16 +178,47 @@ ipsum\n".to_string();
             (
                 test.input,
                 test.expected,
-                test.split
+                test.split,
+                test.options
             ),
             (
                 input,
                 expect,
-                split
+                split,
+                None
             )
         );
     }
 
+    #[test]
+    fn options_parsing() {
+        #[derive(serde::Deserialize, Debug, PartialEq, Default)]
+        #[serde(default)]
+        struct Options {
+            level: u8,
+            class: String
+        }
+
+        let tested = "+++
+level = 3
++++
+...
+# Lorem
+...
+<h1>Lorem</h1>";
+        let test = Test::from(tested);
+        assert_eq!(test.split, "...");
+        assert_eq!(test.input, "# Lorem\n");
+        assert_eq!(
+            test.options::<Options>().unwrap(),
+            Options { level: 3, class: String::new() }
+        );
+        assert_eq!(
+            Test::from("...\n# Lorem\n...\n<h1>Lorem</h1>").options::<Options>().unwrap(),
+            Options::default()
+        );
+    }
+
 
     #[test]
     fn test_tests() {
@@ -116,5 +233,72 @@ ipsum
 <p>ipsum</p>";
         assert!(Test::from(tested).output_matches(parser));
     }
-    
+
+    #[test]
+    fn whitespace_insensitive() {
+        let parser = Test::default_parser();
+        let tested = "...\n- Lorem\n...\n<ul>\n  <li>Lorem</li>\n</ul>";
+        assert!(!Test::from(tested).output_matches(&parser));
+        assert!(Test::from(tested).ignoring_whitespace().output_matches(&parser));
+    }
+
+    #[test]
+    fn blessing() {
+        let path = std::env::temp_dir().join("testing-blessing.md");
+        std::fs::write(&path, "+++\nlevel = 2\n+++\n...\n# Lorem\n...\n<h2>Lorem</h2>\n").unwrap();
+        let test = Test::from_file(&path).unwrap();
+        test.bless(&path, "<h1>Lorem</h1>\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "+++\nlevel = 2\n+++\n...\n# Lorem\n...\n<h1>Lorem</h1>\n"
+        );
+        assert!(Test::from_file(&path).unwrap().output_matches(&Test::default_parser()));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn spec_parsing() {
+        let tested = "+++
+level = 2
++++
+# Headings
+
+```````````````````````````````` example
+# Lorem
+.
+<h1>Lorem</h1>
+````````````````````````````````
+
+Text in between is ignored.
+
+```````````````````````````````` example
+→ipsum
+.
+<pre><code>ipsum
+</code></pre>
+````````````````````````````````
+";
+        let spec = Spec::from("spec.txt".into(), tested);
+        let examples = spec.examples();
+        assert_eq!(examples.len(), 2);
+        assert_eq!((examples[0].number, examples[0].line), (1, 6));
+        assert_eq!((examples[1].number, examples[1].line), (2, 14));
+        assert_eq!(examples[1].test.input, "\tipsum\n");
+        assert_eq!(examples[1].test.options, Some("level = 2\n".to_string()));
+
+        let parser = Test::default_parser();
+        spec.run(&parser);
+        assert_eq!(Spec::from("spec.txt".into(), tested).only(&[2]).examples()[0].line, 14);
+    }
+
+    #[test]
+    #[should_panic(expected = "spec.txt:1: example 1 failed")]
+    fn spec_failure() {
+        let tested = "```````````````````````````````` example
+# Lorem
+.
+<h2>Lorem</h2>
+````````````````````````````````";
+        Spec::from("spec.txt".into(), tested).run(&Test::default_parser());
+    }
 }
@@ -0,0 +1,127 @@
+use std::path::{ Path, PathBuf };
+use markdown_it::MarkdownIt;
+use serde::de::DeserializeOwned;
+use crate::{ Test, OPTIONS_DELIMITER, diff };
+
+// Same fences as the CommonMark spec.txt.
+const EXAMPLE_OPEN: &str = "```````````````````````````````` example";
+const EXAMPLE_CLOSE: &str = "````````````````````````````````";
+const EXAMPLE_SPLIT: &str = ".";
+
+/// A single case of a `Spec`.
+pub struct Example {
+    pub number: usize, // numbered from 1, in the order they're in the file.
+    pub line: usize, // line of the opening fence.
+    pub test: Test
+}
+
+/// A file with many cases, in the style of the CommonMark spec.txt:
+///
+/// ~~~text
+/// Any text outside of the examples is ignored.
+///
+/// ```````````````````````````````` example
+/// ## Lorem
+/// .
+/// <h2>Lorem</h2>
+/// ````````````````````````````````
+/// ~~~
+///
+/// As in spec.txt, `→` stands for a tab. Like a `Test`, a spec can start with a TOML header surrounded by
+/// `+++`, holding the plugin options used for all of its examples.
+pub struct Spec {
+    pub path: PathBuf,
+    options: Option<String>,
+    examples: Vec<Example>
+}
+
+impl Spec {
+    pub fn from(path: PathBuf, spec: &str) -> Self {
+        let mut lines = spec.lines().enumerate().peekable();
+        let mut options = None;
+        if lines.peek().map(|(_, line)| *line) == Some(OPTIONS_DELIMITER) {
+            lines.next();
+            let mut header = String::new();
+            for (_, line) in lines.by_ref() {
+                if line == OPTIONS_DELIMITER {
+                    break;
+                }
+                header.push_str(&(line.to_string() + "\n"));
+            }
+            options = Some(header);
+        }
+
+        let mut examples = Vec::new();
+        while let Some((index, line)) = lines.next() {
+            if line != EXAMPLE_OPEN {
+                continue;
+            }
+            let mut input = String::new();
+            let mut expected = String::new();
+            for (_, line) in lines.by_ref() {
+                if line == EXAMPLE_SPLIT {
+                    break;
+                }
+                input.push_str(&(line.replace('→', "\t") + "\n"));
+            }
+            for (_, line) in lines.by_ref() {
+                if line == EXAMPLE_CLOSE {
+                    break;
+                }
+                expected.push_str(&(line.replace('→', "\t") + "\n"));
+            }
+            examples.push(Example {
+                number: examples.len() + 1,
+                line: index + 1,
+                test: Test {
+                    input,
+                    expected,
+                    split: EXAMPLE_SPLIT.to_string(),
+                    options: options.clone(),
+                    path: None,
+                    ignore_whitespace: false
+                }
+            });
+        }
+
+        Spec { path, options, examples }
+    }
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(Spec::from(path.as_ref().to_path_buf(), &contents))
+    }
+
+    /// Plugin options from the spec's header, see `Test::options`.
+    pub fn options<T: DeserializeOwned>(&self) -> Result<T, toml::de::Error> {
+        toml::from_str(self.options.as_deref().unwrap_or(""))
+    }
+
+    pub fn examples(&self) -> &[Example] {
+        &self.examples
+    }
+
+    /// Keeps only the examples with the given numbers, e.g. to look into a single failing one.
+    pub fn only(mut self, numbers: &[usize]) -> Self {
+        self.examples.retain(|example| numbers.contains(&example.number));
+        self
+    }
+
+    /// Runs every example, panicking with the location of each failing one.
+    pub fn run(&self, md: &MarkdownIt) {
+        let mut failures = Vec::new();
+        for example in &self.examples {
+            let (actual, expected) = example.test.output(md);
+            if actual == expected { continue; }
+            failures.push(format!(
+                "{}:{}: example {} failed\n{}",
+                self.path.display(), example.line, example.number, diff(&expected, &actual)
+            ));
+        }
+        if !failures.is_empty() {
+            panic!(
+                "{} of {} examples failed\n\n{}",
+                failures.len(), self.examples.len(), failures.join("\n\n")
+            );
+        }
+    }
+}
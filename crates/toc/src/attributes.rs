@@ -0,0 +1,74 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    parser::{
+        core::CoreRule,
+        extset::NodeExt,
+        inline::InlineRoot
+    }
+};
+use crate::heading_level;
+
+/// Attributes given to a heading by ending it with `{...}`, e.g. `## Long title {.no-toc}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HeadingAttributes {
+    pub no_toc: bool, // .no-toc: leave the heading out of the table of contents.
+    pub unnumbered: bool, // .unnumbered: don't give the heading a section number.
+    pub label: Option<String>, // toc="Short": text of the heading's entry in the table of contents.
+    pub id: Option<String> // #id: id of the heading, instead of a generated one.
+}
+
+impl NodeExt for HeadingAttributes {}
+
+impl HeadingAttributes {
+    // Splits the attributes off the end of a heading's text. Nothing is split off unless every
+    // attribute in the braces is known, so other uses of braces are left as they were.
+    fn parse(content: &str) -> Option<(&str, HeadingAttributes)> {
+        let trimmed = content.trim_end();
+        if !trimmed.ends_with('}') { return None; }
+        let open = trimmed.rfind('{')?;
+        let inner = &trimmed[open + 1..trimmed.len() - 1];
+
+        let mut attributes = HeadingAttributes::default();
+        let mut rest = inner.trim_start();
+        while !rest.is_empty() {
+            if let Some(value) = rest.strip_prefix("toc=\"") {
+                let end = value.find('"')?;
+                attributes.label = Some(value[..end].to_string());
+                rest = &value[end + 1..];
+            } else {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                match &rest[..end] {
+                    ".no-toc" => attributes.no_toc = true,
+                    ".unnumbered" => attributes.unnumbered = true,
+                    id if id.len() > 1 && id.starts_with('#') => attributes.id = Some(id[1..].to_string()),
+                    _ => return None
+                }
+                rest = &rest[end..];
+            }
+            if !rest.is_empty() && !rest.starts_with(char::is_whitespace) { return None; }
+            rest = rest.trim_start();
+        }
+        if attributes == HeadingAttributes::default() { return None; }
+
+        Some((trimmed[..open].trim_end(), attributes))
+    }
+}
+
+// Runs before inline parsing, so the attributes never end up as text.
+pub struct HeadingAttributesRule;
+
+impl CoreRule for HeadingAttributesRule {
+    fn run(root: &mut Node, _md: &MarkdownIt) {
+        root.walk_mut(|node, _| {
+            if heading_level(node).is_none() { return; }
+            let Some(inline) = node.children.first_mut().and_then(|child| child.cast_mut::<InlineRoot>()) else { return };
+            let Some((content, attributes)) = HeadingAttributes::parse(&inline.content) else { return };
+
+            inline.content = content.to_string();
+            if let Some(id) = &attributes.id {
+                node.attrs.push(("id", id.clone()));
+            }
+            node.ext.insert(attributes);
+        });
+    }
+}
@@ -1,3 +1,18 @@
+mod attributes;
+mod marker;
+mod numbering;
+mod permalink;
+mod slug;
+pub use crate::attributes::HeadingAttributes;
+pub use crate::marker::{ TOCMarker, SectionTOCMarker, NoTOCMarker };
+pub use crate::numbering::{ SectionNumbering, NumberStyle, SectionNumber };
+pub use crate::permalink::{ Permalink, PermalinkPlacement, PermalinkAnchor };
+pub use crate::slug::{ Slugger, GitHubSlugger, Markdown2Slugger, AsciiSlugger };
+use crate::attributes::HeadingAttributesRule;
+use crate::marker::TOCMarkerScanner;
+use crate::numbering::render_number;
+use crate::slug::UniqueSlugs;
+
 use markdown_it::{
     MarkdownIt, Node, NodeValue, Renderer,
     plugins::cmark::block::{
@@ -5,30 +20,64 @@ use markdown_it::{
         lheading::SetextHeader,
     },
     parser::{
+        block::builtin::BlockParserRule,
         core::CoreRule,
-        extset::MarkdownItExt
+        extset::{ MarkdownItExt, NodeExt },
+        inline::builtin::InlineParserRule
     }
 };
+use std::collections::HashSet;
 use std::vec::Vec;
 use unbox_box::BoxExt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableOfContentsItem {
     pub slug: String,
     pub title: String,
     pub level: u8,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub number: Option<String>, // section number, only set if TOCOptions::numbering is.
     pub children: Box<Vec<TableOfContentsItem>>
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum TOCStyle {
+    Nested, // headings nested in <ol>s, below toc_heading.
+    Collapsible { open: bool }, // same as Nested, but in a <details>; toc_heading's text is used as the <summary>.
+    Flat // every heading in one <ul>, regardless of level.
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
 pub struct TOCOptions {
     pub allow_titles_in_toc: bool, // parse in title (h1)
     pub treat_title_as_h2: bool, // only matters if allow_titles_in_toc is true
     pub toc_class: String,
     pub wrap_in_nav: bool, // whether to wrap the table of contents in a <nav> element.
                            // If true, toc_class is applied to <nav> instead of <ol>.
-    pub toc_heading: Option<( u8, String )> // level of heading to use, recommended is 2, followed
-                                            // by the text in the title.
+    pub toc_heading: Option<( u8, String )>, // level of heading to use, recommended is 2, followed
+                                             // by the text in the title.
+    pub auto_insert: bool, // insert the table of contents before the first heading when the page has
+                           // no [TOC] marker.
+    pub min_level: u8, // headings above this level (h1 being the highest) are left out.
+    pub max_level: u8, // headings below this level are left out.
+    pub min_headings: usize, // amount of listed headings required before the table of contents is
+                             // inserted automatically.
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "slug::deserialize_slugger"))]
+    pub slugger: Box<dyn Slugger>, // turns heading titles into ids; repeated ids get -1, -2, ... appended.
+    pub duplicate_suffix_start: usize, // number appended to the first repeat of an id. markdown2 starts at 2.
+    pub inject: bool, // whether to put the table of contents in the page at all. If false, it's only
+                      // available through table_of_contents().
+    pub numbering: Option<SectionNumbering>, // number sections ("2.3.1 Installation") if set.
+    pub permalink: Option<Permalink>, // add a link to itself to every heading if set.
+    pub style: TOCStyle,
+    pub collapse_depth: Option<usize>, // sub-lists nested deeper than this are collapsed behind their entry; the
+                                       // top-level list is 1, so 1 collapses every sub-list.
+    pub section_toc_class: String, // class of the tables of contents listing a single section's subsections.
+    pub section_toc_level: Option<u8> // if set, every heading of this level gets a table of contents of its
+                                      // subsections placed right after it, as if followed by [TOC:section].
 }
 
 impl MarkdownItExt for TOCOptions {}
@@ -39,63 +88,72 @@ impl Default for TOCOptions {
             treat_title_as_h2: false,
             toc_class: "table_of_contents".to_string(),
             wrap_in_nav: true,
-            toc_heading: Some((2, "Contents".to_string()))
+            toc_heading: Some((2, "Contents".to_string())),
+            auto_insert: true,
+            min_level: 1,
+            max_level: 6,
+            min_headings: 2,
+            slugger: Box::new(GitHubSlugger),
+            duplicate_suffix_start: 1,
+            inject: true,
+            numbering: None,
+            permalink: None,
+            style: TOCStyle::Nested,
+            collapse_depth: None,
+            section_toc_class: "section_table_of_contents".to_string(),
+            section_toc_level: None
         }
     }
 }
 
 impl TableOfContentsItem {
-    fn push(&mut self, item: TableOfContentsItem, depth_limit: u8) {
-        let child_count = self.children.unbox_ref().len();
-        if depth_limit > self.level+1 && child_count != 0 {
-            self.children.unbox_mut()[child_count - 1].push(item, depth_limit-1);
-            return;
+    // Nests the item under the last entry for as long as that entry is of a higher level, so
+    // skipped levels (h2 followed by h4) still end up exactly one list deeper.
+    fn insert(items: &mut Vec<TableOfContentsItem>, item: TableOfContentsItem) {
+        match items.last_mut() {
+            Some(last) if last.level < item.level => Self::insert(last.children.unbox_mut(), item),
+            _ => items.push(item)
         }
-        self.children.unbox_mut().push(item);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TOC {
     contents: Vec<TableOfContentsItem>,
-    min_level: u8,
+    class: String,
+    number_class: Option<String>, // class of section numbers, if they're shown.
     wrap_in_nav: bool,
-    toc_heading: Option<( u8, String )>
+    toc_heading: Option<( u8, String )>,
+    style: TOCStyle,
+    collapse_depth: Option<usize>
 }
 
 impl NodeValue for TOC {
     fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
-
-        fn render_item(item: &TableOfContentsItem, fmt: &mut dyn Renderer) {
-            fmt.cr();
-            fmt.open("li", &[]);
-
-            let mut link_href = String::from("#");
-            link_href.push_str(&item.slug);
-            fmt.cr();
-            fmt.open("a", &[("href", link_href)]);
-            fmt.text(&item.title);
-            fmt.close("a");
-            if item.children.len() != 0 {
-                fmt.cr();
-                fmt.open("ol", &[]);
-                item.children.unbox_ref().iter().for_each(|child| {
-                    render_item(&(child), fmt);
-                });
-                fmt.cr();
-                fmt.close("ol");
-            }
-            fmt.cr();
-            fmt.close("li");
-        }
         let attrs = node.attrs.clone();
+        let collapsible = match self.style {
+            TOCStyle::Collapsible { open } => Some(open),
+            _ => None
+        };
+        let list_tag = if self.style == TOCStyle::Flat { "ul" } else { "ol" };
 
         fmt.cr();
         if self.wrap_in_nav {
             fmt.open("nav", &attrs);
             fmt.cr();
         }
-        if let Some(heading) = &self.toc_heading {
+        if let Some(open) = collapsible {
+            let mut details_attrs = if !self.wrap_in_nav { attrs.clone() } else { Vec::new() };
+            if open {
+                details_attrs.push(("open", String::new()));
+            }
+            fmt.open("details", &details_attrs);
+            fmt.cr();
+            fmt.open("summary", &[]);
+            fmt.text(self.toc_heading.as_ref().map_or("Contents", |heading| &heading.1));
+            fmt.close("summary");
+            fmt.cr();
+        } else if let Some(heading) = &self.toc_heading {
             let heading_tag = String::from("h") + &heading.0.to_string();
             fmt.open(&heading_tag, &[]);
             fmt.text(&heading.1);
@@ -103,14 +161,32 @@ impl NodeValue for TOC {
             fmt.cr();
         }
         fmt.open(
-            "ol",
-            if !self.wrap_in_nav { &attrs } else { &[] }
+            list_tag,
+            if !self.wrap_in_nav && collapsible.is_none() { &attrs } else { &[] }
         );
-        self.contents.iter().for_each(|item| {
-            render_item(item, fmt);
-        });
+        if self.style == TOCStyle::Flat {
+            fn render_flat(toc: &TOC, items: &[TableOfContentsItem], fmt: &mut dyn Renderer) {
+                items.iter().for_each(|item| {
+                    fmt.cr();
+                    fmt.open("li", &[]);
+                    toc.render_link(item, fmt);
+                    fmt.cr();
+                    fmt.close("li");
+                    render_flat(toc, item.children.unbox_ref(), fmt);
+                });
+            }
+            render_flat(self, &self.contents, fmt);
+        } else {
+            self.contents.iter().for_each(|item| {
+                self.render_item(item, 1, fmt);
+            });
+        }
         fmt.cr();
-        fmt.close("ol");
+        fmt.close(list_tag);
+        if collapsible.is_some() {
+            fmt.cr();
+            fmt.close("details");
+        }
         if self.wrap_in_nav {
             fmt.cr();
             fmt.close("nav");
@@ -119,23 +195,135 @@ impl NodeValue for TOC {
     }
 }
 
+impl NodeExt for TOC {}
+
 impl TOC {
-    fn push(&mut self, item: TableOfContentsItem, depth_limit: u8) {
-        println!("{}", depth_limit);
-        let child_count = self.contents.len();
-        if depth_limit > self.min_level && child_count != 0 {
-            self.contents[child_count - 1].push(item, depth_limit - 1);
-            return;
+    fn render_link(&self, item: &TableOfContentsItem, fmt: &mut dyn Renderer) {
+        let mut link_href = String::from("#");
+        link_href.push_str(&item.slug);
+        fmt.cr();
+        fmt.open("a", &[("href", link_href)]);
+        if let (Some(number), Some(class)) = (&item.number, &self.number_class) {
+            render_number(number, class, fmt);
+        }
+        fmt.text(&item.title);
+        fmt.close("a");
+    }
+
+    // Depth is that of the list the item is in, the top-level list being 1.
+    fn render_item(&self, item: &TableOfContentsItem, depth: usize, fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("li", &[]);
+
+        if item.children.is_empty() {
+            self.render_link(item, fmt);
+        } else {
+            let collapsed = self.collapse_depth.is_some_and(|collapse_depth| depth >= collapse_depth);
+            if collapsed {
+                fmt.cr();
+                fmt.open("details", &[]);
+                fmt.cr();
+                fmt.open("summary", &[]);
+                self.render_link(item, fmt);
+                fmt.cr();
+                fmt.close("summary");
+            } else {
+                self.render_link(item, fmt);
+            }
+            fmt.cr();
+            fmt.open("ol", &[]);
+            item.children.unbox_ref().iter().for_each(|child| {
+                self.render_item(child, depth + 1, fmt);
+            });
+            fmt.cr();
+            fmt.close("ol");
+            if collapsed {
+                fmt.cr();
+                fmt.close("details");
+            }
         }
-        println!("---");
-        self.min_level = item.level;
-        self.contents.push(item);
+        fmt.cr();
+        fmt.close("li");
+    }
+
+    fn push(&mut self, item: TableOfContentsItem) {
+        TableOfContentsItem::insert(&mut self.contents, item);
+    }
+
+    fn node(&self) -> Node {
+        let mut node = Node::new(self.clone());
+        node.attrs.push(("class", self.class.clone()));
+        node
+    }
+
+    fn find<'a>(items: &'a [TableOfContentsItem], slug: &str) -> Option<&'a TableOfContentsItem> {
+        items.iter().find_map(|item| {
+            if item.slug == slug {
+                return Some(item);
+            }
+            Self::find(item.children.unbox_ref(), slug)
+        })
+    }
+
+    // Lists the subsections of a single entry, without a heading of its own.
+    fn section(&self, item: &TableOfContentsItem, class: &str) -> Option<TOC> {
+        if item.children.is_empty() {
+            return None;
+        }
+        Some(TOC {
+            contents: item.children.unbox_ref().clone(),
+            class: class.to_string(),
+            number_class: self.number_class.clone(),
+            wrap_in_nav: self.wrap_in_nav,
+            toc_heading: None,
+            style: self.style,
+            collapse_depth: self.collapse_depth
+        })
+    }
+
+    /// Top-level entries; nested headings are under their `children`.
+    pub fn items(&self) -> &[TableOfContentsItem] {
+        &self.contents
+    }
+
+    /// Amount of entries, nested ones included.
+    pub fn len(&self) -> usize {
+        fn count(items: &[TableOfContentsItem]) -> usize {
+            items.iter().map(|item| 1 + count(item.children.unbox_ref())).sum()
+        }
+        count(&self.contents)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+
+    /// Renders the table of contents on its own, e.g. for a sidebar. This is the same HTML that's
+    /// put in the page.
+    pub fn render(&self) -> String {
+        self.node().render()
     }
 }
 
+/// Returns the table of contents of a parsed document, whether or not it was put in the page.
+/// `None` if the page has no headings to list, or opted out using `[NOTOC]`.
+pub fn table_of_contents(root: &Node) -> Option<&TOC> {
+    root.ext.get::<TOC>()
+}
+
+
+fn heading_id(node: &Node) -> Option<&str> {
+    node.attrs.iter().find(|(name, _)| *name == "id").map(|(_, id)| id.as_str())
+}
 
-fn sluggify(name: &str) -> String {
-    name.to_string().replace(|c| !char::is_alphanumeric(c) && c != ' ', "").replace(" ", "-").to_lowercase()
+pub(crate) fn heading_level(node: &Node) -> Option<u8> {
+    if let Some(item) = node.cast::<ATXHeading>() {
+        return Some(item.level);
+    }
+    if let Some(item) = node.cast::<SetextHeader>() {
+        return Some(item.level);
+    }
+    None
 }
 
 struct TableOfContentsDetect;
@@ -145,95 +333,211 @@ impl CoreRule for TableOfContentsDetect {
         struct Heading {
             level: u8,
             title: String,
-            slug: String
+            slug: String,
+            attributes: HeadingAttributes
         }
 
-        let mut disorganized_headings: Vec<Heading> = Vec::new();
-        let mut index = 0;
-        let mut head_count = 0;
-        let mut first_heading: Option<u16> = None;
-        root.walk_post_mut(|node, _| {
-            fn get_level(node: &Node) -> Option<u8> {
-                match node.cast::<ATXHeading>() {
-                    Some(item) => return Some(item.level),
-                    None => ()
-                };
-                match node.cast::<SetextHeader>() {
-                    Some(item) => return Some(item.level),
-                    None => ()
-                };
-                return None
-            }
-
-            index += 1;
+        let default_opts = TOCOptions::default();
+        let opts = md.ext.get::<TOCOptions>().unwrap_or(&default_opts);
 
-            let level = match get_level(node) {
-                None => return (),
-                Some(l) => l
-            };
+        // If another plugin (or a `{#id}` attribute) sets the id, use that instead; generated ids must not collide
+        // with it. Only the first heading with a given id keeps it, as ids have to be unique within the page.
+        let mut slugs = UniqueSlugs::new(opts.duplicate_suffix_start);
+        root.walk(|node, _| {
+            if heading_level(node).is_none() { return; }
+            if let Some(id) = heading_id(node) {
+                slugs.reserve(id);
+            }
+        });
 
-            if first_heading == None {
-                first_heading = Some((index - 1) >> 1);
-                // MD-It has been adding a skip text between each element for some reason.
+        let mut explicit_ids = HashSet::new();
+        let mut disorganized_headings: Vec<Heading> = Vec::new();
+        let mut has_marker = false;
+        // Amount of headings before each [TOC:section], the last of which is the section it's in.
+        let mut section_markers: Vec<usize> = Vec::new();
+        let mut disabled = false;
+        root.walk_mut(|node, _| {
+            if node.is::<TOCMarker>() {
+                has_marker = true;
+                return;
+            }
+            if node.is::<SectionTOCMarker>() {
+                section_markers.push(disorganized_headings.len());
+                return;
             }
-            head_count = head_count + 1;
+            if node.is::<NoTOCMarker>() {
+                disabled = true;
+                return;
+            }
+
+            let Some(level) = heading_level(node) else { return };
 
             let title = node.collect_text();
-            let slug = match node.attrs.as_slice() {
-                [("id", id)] => String::from(id),
-                // If another plugin sets the id, use that instead.
-                _ => {
-                    let slug = sluggify(&title);
+            let slug = match heading_id(node).map(str::to_string) {
+                Some(id) if explicit_ids.insert(id.clone()) => id,
+                Some(id) => {
+                    let slug = slugs.claim(id.clone());
+                    #[cfg(feature = "log")]
+                    log::warn!("heading id {:?} is already used on the page, changed to {:?}", id, slug);
+                    let attr = node.attrs.iter_mut().find(|(name, _)| *name == "id").unwrap();
+                    attr.1 = slug.clone();
+                    slug
+                },
+                None => {
+                    let slug = slugs.claim(opts.slugger.slug(&title));
                     node.attrs.push(("id", slug.clone()));
                     slug
                 }
             };
-            
-            let header_tag = Heading {
-                title: title,
-                slug: String::from(slug),
-                level: level
-            };
-            disorganized_headings.push(header_tag);
-        });
 
-        let default_opts = TOCOptions::default();
-        let opts = md.ext.get::<TOCOptions>().unwrap_or(&default_opts);
+            let attributes = node.ext.get::<HeadingAttributes>().cloned().unwrap_or_default();
+            disorganized_headings.push(Heading { title, slug, level, attributes });
+        });
 
+        let heading_slugs: Vec<String> = disorganized_headings.iter().map(|heading| heading.slug.clone()).collect();
+        let numbering = opts.numbering.as_ref();
         let mut organized_headings = TOC {
             contents: Vec::new(),
-            min_level: 6,
+            class: opts.toc_class.clone(),
+            number_class: numbering.filter(|n| n.number_toc).map(|n| n.class.clone()),
             wrap_in_nav: opts.wrap_in_nav,
-            toc_heading: opts.toc_heading.clone()
+            toc_heading: opts.toc_heading.clone(),
+            style: opts.style,
+            collapse_depth: opts.collapse_depth
         };
+        let mut unnumbered = Vec::new();
         for heading in disorganized_headings {
-            let head = TableOfContentsItem {
-                title: heading.title,
-                slug: heading.slug,
-                level: heading.level,
-                children: Box::new(Vec::new())
-            };
+            if heading.attributes.unnumbered {
+                unnumbered.push(heading.slug.clone());
+            }
+            if heading.attributes.no_toc { continue; }
             if heading.level == 1 && !opts.allow_titles_in_toc { continue; }
-            organized_headings.push(
-                head,
-                if heading.level == 1 && opts.treat_title_as_h2 { 2 } else { heading.level }
-            );
+            let level = if heading.level == 1 && opts.treat_title_as_h2 { 2 } else { heading.level };
+            if level < opts.min_level || level > opts.max_level { continue; }
+
+            organized_headings.push(TableOfContentsItem {
+                title: heading.attributes.label.unwrap_or(heading.title),
+                slug: heading.slug,
+                level,
+                number: None,
+                children: Box::default()
+            });
         }
 
-        if head_count < 2 {
-            return ();
+        if let Some(numbering) = numbering {
+            let numbers = numbering.apply(&mut organized_headings.contents, &unnumbered);
+            if numbering.number_headings {
+                root.walk_mut(|node, _| {
+                    if heading_level(node).is_none() { return; }
+                    let Some(number) = heading_id(node).and_then(|id| numbers.get(id)) else { return };
+                    let number = Node::new(SectionNumber {
+                        number: number.clone(),
+                        class: numbering.class.clone()
+                    });
+                    node.children.insert(0, number);
+                });
+            }
         }
 
-        let mut table_of_contents = Node::new(organized_headings);
-        table_of_contents.attrs.push(("class", opts.toc_class.clone()));
-        match first_heading {
-            Some(i) => root.children.insert(i.into(), table_of_contents),
-            None => ()
-        };
+        if let Some(permalink) = &opts.permalink {
+            root.walk_mut(|node, _| {
+                if heading_level(node).is_none() { return; }
+                let Some(slug) = heading_id(node).map(str::to_string) else { return };
+                permalink.add_to(node, &slug);
+            });
+        }
+
+        if organized_headings.is_empty() {
+            #[cfg(feature = "log")]
+            log::debug!("no table of contents: no headings to list");
+            return;
+        }
+        #[cfg(feature = "log")]
+        log::debug!("table of contents built with {} entries", organized_headings.len());
+        if !disabled {
+            root.ext.insert(organized_headings.clone());
+        }
+
+        if !opts.inject {
+            return;
+        }
+
+        let mut sections = section_markers.iter().map(|&count| {
+            // The closest heading which is listed is the enclosing section.
+            let item = heading_slugs[..count].iter().rev()
+                .find_map(|slug| TOC::find(&organized_headings.contents, slug))?;
+            organized_headings.section(item, &opts.section_toc_class)
+        }).collect::<Vec<Option<TOC>>>().into_iter();
+        root.walk_mut(|node, _| {
+            if !node.is::<SectionTOCMarker>() { return; }
+            if let Some(Some(section)) = sections.next() {
+                *node = section.node();
+            }
+        });
+
+        if let Some(level) = opts.section_toc_level {
+            fn insert_sections(node: &mut Node, toc: &TOC, level: u8, class: &str) {
+                let mut i = 0;
+                while i < node.children.len() {
+                    let child = &mut node.children[i];
+                    i += 1;
+                    if heading_level(child).is_none() {
+                        insert_sections(child, toc, level, class);
+                        continue;
+                    }
+                    let Some(item) = heading_id(child).and_then(|id| TOC::find(&toc.contents, id)) else { continue };
+                    if item.level != level { continue; }
+                    if let Some(section) = toc.section(item, class) {
+                        node.children.insert(i, section.node());
+                        i += 1;
+                    }
+                }
+            }
+            insert_sections(root, &organized_headings, level, &opts.section_toc_class);
+        }
+
+        // [NOTOC] only takes out the table of contents of the whole page.
+        if disabled {
+            #[cfg(feature = "log")]
+            log::debug!("table of contents disabled by [NOTOC]");
+            return;
+        }
+
+        if has_marker {
+            // Markers are always honoured, as long as there's something to list.
+            root.walk_mut(|node, _| {
+                if !node.is::<TOCMarker>() { return; }
+                *node = organized_headings.node();
+            });
+            return;
+        }
+
+        if !opts.auto_insert || organized_headings.len() < opts.min_headings {
+            return;
+        }
+
+        // Placed before the top-level block holding the first heading.
+        let first_heading = root.children.iter().position(|child| {
+            let mut found = false;
+            child.walk(|node, _| found |= heading_level(node).is_some());
+            found
+        });
+        if let Some(i) = first_heading {
+            root.children.insert(i, organized_headings.node());
+        }
     }
 }
 
 pub fn add(md: &mut MarkdownIt) {
     // insert this rule into parser
-    md.add_rule::<TableOfContentsDetect>();
+    md.block.add_rule::<TOCMarkerScanner>().before_all();
+    md.add_rule::<HeadingAttributesRule>().after::<BlockParserRule>().before::<InlineParserRule>();
+    md.add_rule::<TableOfContentsDetect>().after::<InlineParserRule>();
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: TOCOptions) {
+    md.ext.insert(options);
+    md.block.add_rule::<TOCMarkerScanner>().before_all();
+    md.add_rule::<HeadingAttributesRule>().after::<BlockParserRule>().before::<InlineParserRule>();
+    md.add_rule::<TableOfContentsDetect>().after::<InlineParserRule>();
 }
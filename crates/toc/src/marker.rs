@@ -0,0 +1,56 @@
+use markdown_it::{
+    Node, NodeValue, Renderer,
+    parser::block::{ BlockRule, BlockState }
+};
+
+// Lines which are replaced with the table of contents.
+const TOC_MARKERS: [&str; 3] = [ "[TOC]", "[[_TOC_]]", "<!-- toc -->" ];
+// Lines which are replaced with a table of contents of the section they're in.
+const SECTION_TOC_MARKERS: [&str; 1] = [ "[TOC:section]" ];
+// Lines which disable the table of contents for the whole page.
+const NOTOC_MARKERS: [&str; 1] = [ "[NOTOC]" ];
+
+/// Placeholder for `[TOC]`; replaced with the table of contents by the core rule.
+#[derive(Debug)]
+pub struct TOCMarker;
+
+impl NodeValue for TOCMarker {
+    // Renders nothing if the page doesn't end up with a table of contents.
+    fn render(&self, _node: &Node, _fmt: &mut dyn Renderer) {}
+}
+
+/// Placeholder for `[TOC:section]`; replaced with the subsections of the enclosing section.
+#[derive(Debug)]
+pub struct SectionTOCMarker;
+
+impl NodeValue for SectionTOCMarker {
+    fn render(&self, _node: &Node, _fmt: &mut dyn Renderer) {}
+}
+
+/// Left in place of `[NOTOC]`; disables the table of contents for the page.
+#[derive(Debug)]
+pub struct NoTOCMarker;
+
+impl NodeValue for NoTOCMarker {
+    fn render(&self, _node: &Node, _fmt: &mut dyn Renderer) {}
+}
+
+pub struct TOCMarkerScanner;
+
+impl BlockRule for TOCMarkerScanner {
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        if state.line_indent(state.line) >= state.md.max_indent { return None; }
+
+        let line = state.get_line(state.line).trim_end();
+        if TOC_MARKERS.iter().any(|marker| line.eq_ignore_ascii_case(marker)) {
+            return Some((Node::new(TOCMarker), 1));
+        }
+        if SECTION_TOC_MARKERS.iter().any(|marker| line.eq_ignore_ascii_case(marker)) {
+            return Some((Node::new(SectionTOCMarker), 1));
+        }
+        if NOTOC_MARKERS.iter().any(|marker| line.eq_ignore_ascii_case(marker)) {
+            return Some((Node::new(NoTOCMarker), 1));
+        }
+        None
+    }
+}
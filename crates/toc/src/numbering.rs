@@ -0,0 +1,147 @@
+use markdown_it::{ Node, NodeValue, Renderer };
+use std::collections::HashMap;
+use unbox_box::BoxExt;
+use crate::TableOfContentsItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum NumberStyle {
+    Decimal,    // 1, 2, 3
+    LowerAlpha, // a, b, c
+    UpperAlpha, // A, B, C
+    LowerRoman, // i, ii, iii
+    UpperRoman  // I, II, III
+}
+
+impl NumberStyle {
+    fn format(&self, n: usize) -> String {
+        match self {
+            NumberStyle::Decimal => n.to_string(),
+            NumberStyle::LowerAlpha => alpha(n),
+            NumberStyle::UpperAlpha => alpha(n).to_uppercase(),
+            NumberStyle::LowerRoman => roman(n),
+            NumberStyle::UpperRoman => roman(n).to_uppercase()
+        }
+    }
+}
+
+// 1 -> a, 26 -> z, 27 -> aa
+fn alpha(mut n: usize) -> String {
+    let mut letters = Vec::new();
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+fn roman(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+        (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")
+    ];
+    let mut numeral = String::new();
+    for (value, letters) in NUMERALS {
+        while n >= value {
+            numeral.push_str(letters);
+            n -= value;
+        }
+    }
+    numeral
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct SectionNumbering {
+    pub styles: Vec<NumberStyle>, // style of each nesting level; the last one is used for any deeper levels.
+    pub separator: String, // placed between the numbers of each level, "2.3.1".
+    pub appendix_from: Option<String>, // slug of the first top-level section to number as an appendix
+                                       // (A, B, C), along with every top-level section after it.
+    pub exclude: Vec<String>, // slugs of headings which aren't numbered, along with their subsections.
+    pub number_headings: bool, // prefix the headings themselves with their number.
+    pub number_toc: bool, // prefix the entries of the table of contents with their number.
+    pub class: String // class of the <span> holding the number.
+}
+
+impl Default for SectionNumbering {
+    fn default() -> Self {
+        SectionNumbering {
+            styles: vec![NumberStyle::Decimal],
+            separator: ".".to_string(),
+            appendix_from: None,
+            exclude: Vec::new(),
+            number_headings: true,
+            number_toc: true,
+            class: "section-number".to_string()
+        }
+    }
+}
+
+impl SectionNumbering {
+    fn style(&self, depth: usize) -> NumberStyle {
+        *self.styles.get(depth).or(self.styles.last()).unwrap_or(&NumberStyle::Decimal)
+    }
+
+    fn excluded(&self, slug: &str, unnumbered: &[String]) -> bool {
+        self.exclude.iter().chain(unnumbered).any(|excluded| excluded == slug)
+    }
+
+    // Sets `number` on every item which isn't excluded, returning the numbers by slug. Headings marked
+    // with {.unnumbered} are passed in as `unnumbered`.
+    pub(crate) fn apply(&self, items: &mut [TableOfContentsItem], unnumbered: &[String]) -> HashMap<String, String> {
+        let mut numbers = HashMap::new();
+        let mut count = 0;
+        let mut appendix_count = 0;
+        let mut in_appendix = false;
+        for item in items.iter_mut() {
+            if self.excluded(&item.slug, unnumbered) { continue; }
+            in_appendix |= self.appendix_from.as_ref() == Some(&item.slug);
+
+            let number = if in_appendix {
+                appendix_count += 1;
+                NumberStyle::UpperAlpha.format(appendix_count)
+            } else {
+                count += 1;
+                self.style(0).format(count)
+            };
+            self.apply_nested(item, number, 1, unnumbered, &mut numbers);
+        }
+        numbers
+    }
+
+    fn apply_nested(
+        &self, item: &mut TableOfContentsItem, number: String, depth: usize,
+        unnumbered: &[String], numbers: &mut HashMap<String, String>
+    ) {
+        let mut count = 0;
+        for child in item.children.unbox_mut().iter_mut() {
+            if self.excluded(&child.slug, unnumbered) { continue; }
+            count += 1;
+            let child_number = number.clone() + &self.separator + &self.style(depth).format(count);
+            self.apply_nested(child, child_number, depth + 1, unnumbered, numbers);
+        }
+        numbers.insert(item.slug.clone(), number.clone());
+        item.number = Some(number);
+    }
+}
+
+/// Number put in front of a heading's text.
+#[derive(Debug)]
+pub struct SectionNumber {
+    pub number: String,
+    pub class: String
+}
+
+impl NodeValue for SectionNumber {
+    fn render(&self, _node: &Node, fmt: &mut dyn Renderer) {
+        render_number(&self.number, &self.class, fmt);
+    }
+}
+
+pub(crate) fn render_number(number: &str, class: &str, fmt: &mut dyn Renderer) {
+    fmt.open("span", &[("class", class.to_string())]);
+    fmt.text(number);
+    fmt.close("span");
+    fmt.text(" ");
+}
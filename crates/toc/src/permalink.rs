@@ -0,0 +1,59 @@
+use markdown_it::{ Node, NodeValue, Renderer };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum PermalinkPlacement {
+    Before, // in front of the heading's text.
+    After   // behind the heading's text.
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct Permalink {
+    pub symbol: String, // contents of the link; not escaped, so it can be an entity or an icon.
+    pub placement: PermalinkPlacement,
+    pub class: String,
+    pub aria_label: Option<String> // {title} is replaced with the heading's text.
+}
+
+impl Default for Permalink {
+    fn default() -> Self {
+        Permalink {
+            symbol: "¶".to_string(),
+            placement: PermalinkPlacement::After,
+            class: "anchor".to_string(),
+            aria_label: Some("Permalink to {title}".to_string())
+        }
+    }
+}
+
+impl Permalink {
+    pub(crate) fn add_to(&self, heading: &mut Node, slug: &str) {
+        let title = heading.collect_text();
+        let mut anchor = Node::new(PermalinkAnchor { symbol: self.symbol.clone() });
+        anchor.attrs.push(("class", self.class.clone()));
+        anchor.attrs.push(("href", String::from("#") + slug));
+        if let Some(label) = &self.aria_label {
+            anchor.attrs.push(("aria-label", label.replace("{title}", &title)));
+        }
+
+        match self.placement {
+            PermalinkPlacement::Before => heading.children.insert(0, anchor),
+            PermalinkPlacement::After => heading.children.push(anchor)
+        }
+    }
+}
+
+/// Link to the heading it's placed in.
+#[derive(Debug)]
+pub struct PermalinkAnchor {
+    pub symbol: String
+}
+
+impl NodeValue for PermalinkAnchor {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("a", &node.attrs);
+        fmt.text_raw(&self.symbol);
+        fmt.close("a");
+    }
+}
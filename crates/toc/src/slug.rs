@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use unicode_normalization::UnicodeNormalization;
+
+/// Turns heading titles into ids. Implement this to use your own scheme through `TOCOptions::slugger`.
+pub trait Slugger: Debug + Send + Sync {
+    fn slug(&self, title: &str) -> String;
+}
+
+/// Same ids as GitHub: lowercased, punctuation removed, and every space turned into a hyphen.
+/// Non-ASCII letters are kept as they are (`Über uns` becomes `über-uns`).
+#[derive(Debug, Default)]
+pub struct GitHubSlugger;
+
+impl Slugger for GitHubSlugger {
+    fn slug(&self, title: &str) -> String {
+        title.trim().to_lowercase().chars().filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None
+        }).collect()
+    }
+}
+
+/// Same ids as markdown2's `header-ids` extra: accents are stripped, anything else that isn't
+/// ASCII is dropped, and runs of spaces and hyphens become a single hyphen.
+#[derive(Debug, Default)]
+pub struct Markdown2Slugger;
+
+impl Slugger for Markdown2Slugger {
+    fn slug(&self, title: &str) -> String {
+        let ascii: String = title.nfkd().filter(|c| c.is_ascii()).collect();
+        let stripped: String = ascii.chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-' || c.is_ascii_whitespace())
+            .collect();
+
+        let mut slug = String::new();
+        let mut hyphen = false;
+        for c in stripped.trim().to_lowercase().chars() {
+            if c == '-' || c.is_ascii_whitespace() {
+                hyphen = true;
+                continue;
+            }
+            if hyphen {
+                slug.push('-');
+                hyphen = false;
+            }
+            slug.push(c);
+        }
+        slug
+    }
+}
+
+/// Transliterates everything to ASCII first (`日本語` becomes `ri-ben-yu`), so ids stay readable
+/// in places which don't cope well with Unicode.
+#[derive(Debug, Default)]
+pub struct AsciiSlugger;
+
+impl Slugger for AsciiSlugger {
+    fn slug(&self, title: &str) -> String {
+        deunicode::deunicode(title)
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<&str>>()
+            .join("-")
+    }
+}
+
+// Sluggers are picked by name in options files: "github", "markdown2" or "ascii".
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_slugger<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Box<dyn Slugger>, D::Error> {
+    use serde::Deserialize;
+    match String::deserialize(deserializer)?.as_str() {
+        "github" => Ok(Box::new(GitHubSlugger)),
+        "markdown2" => Ok(Box::new(Markdown2Slugger)),
+        "ascii" => Ok(Box::new(AsciiSlugger)),
+        other => Err(serde::de::Error::unknown_variant(other, &["github", "markdown2", "ascii"]))
+    }
+}
+
+// Keeps track of the ids on a page, appending -1, -2, ... to repeated ones (or counting from another number).
+#[derive(Debug)]
+pub(crate) struct UniqueSlugs {
+    taken: HashSet<String>,
+    suffix_start: usize
+}
+
+impl UniqueSlugs {
+    pub(crate) fn new(suffix_start: usize) -> Self {
+        Self { taken: HashSet::new(), suffix_start }
+    }
+
+    // Ids set by other plugins are kept as they are, but generated ones mustn't collide with them.
+    pub(crate) fn reserve(&mut self, id: &str) {
+        self.taken.insert(id.to_string());
+    }
+
+    pub(crate) fn claim(&mut self, slug: String) -> String {
+        let slug = if slug.is_empty() { "section".to_string() } else { slug };
+        if self.taken.insert(slug.clone()) {
+            return slug;
+        }
+        let mut count = self.suffix_start;
+        loop {
+            let candidate = format!("{}-{}", slug, count);
+            if self.taken.insert(candidate.clone()) {
+                return candidate;
+            }
+            count += 1;
+        }
+    }
+}
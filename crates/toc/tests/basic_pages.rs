@@ -1,13 +1,73 @@
 use rstest::*;
-use testing::Test;
+use testing::{ Test, Spec };
+use markdown_it_table_of_contents::{ table_of_contents, TOCOptions };
 use std::path::PathBuf;
-use markdown_it_table_of_contents;
 
 #[rstest]
-fn main(#[files("tests/predone/test1-*.md")] path: PathBuf) {
-    let mut parser = Test::default_parser();
-    markdown_it_table_of_contents::add(&mut parser);
+fn main(#[files("tests/predone/*.md")] path: PathBuf) {
     let test = Test::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add_with_options(&mut parser, test.options::<TOCOptions>().unwrap());
     let matches = test.output_matches_log(&parser);
     assert!(matches);
 }
+
+#[test]
+fn exported() {
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add_with_options(&mut parser, TOCOptions {
+        inject: false,
+        ..TOCOptions::default()
+    });
+    let root = parser.parse("## See?\n\n### Lorem?\n\n## Ipsum\n");
+    let toc = table_of_contents(&root).unwrap();
+
+    assert_eq!(toc.len(), 3);
+    assert_eq!(toc.items().len(), 2);
+    assert_eq!(toc.items()[0].children[0].slug, "lorem");
+    assert_eq!(toc.render(), "<nav class=\"table_of_contents\">
+<h2>Contents</h2>
+<ol>
+<li>
+<a href=\"#see\">See?</a>
+<ol>
+<li>
+<a href=\"#lorem\">Lorem?</a>
+</li>
+</ol>
+</li>
+<li>
+<a href=\"#ipsum\">Ipsum</a>
+</li>
+</ol>
+</nav>
+");
+}
+
+#[test]
+fn exported_json() {
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add(&mut parser);
+    let root = parser.parse("## See?\n\n### Lorem?\n");
+    let toc = table_of_contents(&root).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(toc.items()).unwrap(),
+        r#"[{"slug":"see","title":"See?","level":2,"children":[{"slug":"lorem","title":"Lorem?","level":3,"children":[]}]}]"#
+    );
+}
+
+#[rstest]
+fn spec(#[files("tests/spec/*.txt")] path: PathBuf) {
+    let spec = Spec::from_file(path).unwrap();
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add_with_options(&mut parser, spec.options::<TOCOptions>().unwrap());
+    spec.run(&parser);
+}
+
+#[test]
+fn properties() {
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add(&mut parser);
+    testing::assert_properties(&parser);
+}
@@ -0,0 +1,331 @@
+use markdown_it::MarkdownIt;
+
+pub use markdown_it;
+#[cfg(feature = "toc")]
+pub use markdown_it_table_of_contents as toc;
+#[cfg(feature = "footnote")]
+pub use markdown_it_footnotes as footnote;
+#[cfg(feature = "latex")]
+pub use markdown_it_latex as latex;
+#[cfg(feature = "supsub")]
+pub use markdown_it_supsub as supsub;
+#[cfg(feature = "lazyload")]
+pub use markdown_it_lazyload as lazyload;
+#[cfg(feature = "admonition")]
+pub use markdown_it_admonition as admonition;
+#[cfg(feature = "spoiler")]
+pub use markdown_it_spoiler as spoiler;
+#[cfg(feature = "tasklist")]
+pub use markdown_it_tasklist as tasklist;
+#[cfg(feature = "smartypants")]
+pub use markdown_it_smartypants as smartypants;
+#[cfg(feature = "wikitable")]
+pub use markdown_it_wikitable as wikitable;
+#[cfg(feature = "linkattrs")]
+pub use markdown_it_link_attributes as linkattrs;
+
+#[cfg(feature = "toc")]
+use markdown_it_table_of_contents::{ TOCOptions, Markdown2Slugger };
+#[cfg(feature = "footnote")]
+use markdown_it_footnotes::FootnoteOptions;
+#[cfg(feature = "supsub")]
+use markdown_it_supsub::SupSubOptions;
+#[cfg(feature = "lazyload")]
+use markdown_it_lazyload::LazyLoadOptions;
+#[cfg(feature = "admonition")]
+use markdown_it_admonition::AdmonitionOptions;
+#[cfg(feature = "spoiler")]
+use markdown_it_spoiler::SpoilerOptions;
+#[cfg(feature = "tasklist")]
+use markdown_it_tasklist::TaskListOptions;
+#[cfg(feature = "smartypants")]
+use markdown_it_smartypants::SmartyPantsOptions;
+#[cfg(feature = "wikitable")]
+use markdown_it_wikitable::WikiTableOptions;
+#[cfg(feature = "linkattrs")]
+use markdown_it_link_attributes::LinkAttributesOptions;
+
+/// Picks the plugins to add to a CommonMark parser. Plugins are always added in the same order, whichever
+/// order they're picked in, so that the table of contents is built last, from the page as the other
+/// plugins left it.
+///
+/// ```
+/// let md = wikimd::WikiMd::markdown2_compat().build();
+/// let html = md.parse("Lorem ~~ipsum~~[^1]\n\n[^1]: $x^2$").render();
+/// ```
+#[derive(Debug, Default)]
+pub struct WikiMd {
+    html: bool,
+    strikethrough: bool,
+    tables: bool,
+    #[cfg(feature = "wikitable")]
+    wiki_tables: Option<WikiTableOptions>,
+    #[cfg(feature = "latex")]
+    latex: bool,
+    #[cfg(feature = "supsub")]
+    supsub: Option<SupSubOptions>,
+    #[cfg(feature = "lazyload")]
+    lazyload: Option<LazyLoadOptions>,
+    #[cfg(feature = "smartypants")]
+    smartypants: Option<SmartyPantsOptions>,
+    #[cfg(feature = "linkattrs")]
+    link_attributes: Option<LinkAttributesOptions>,
+    #[cfg(feature = "admonition")]
+    admonitions: Option<AdmonitionOptions>,
+    #[cfg(feature = "spoiler")]
+    spoilers: Option<SpoilerOptions>,
+    #[cfg(feature = "tasklist")]
+    task_lists: Option<TaskListOptions>,
+    #[cfg(feature = "footnote")]
+    footnotes: Option<FootnoteOptions>,
+    #[cfg(feature = "toc")]
+    toc: Option<TOCOptions>
+}
+
+impl WikiMd {
+    /// Plain CommonMark, without any plugins.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Raw HTML, and the markdown2 extras used by the Nekoweb Wiki: `toc` and `header-ids` (with the same ids as
+    /// markdown2), `footnotes`, `latex`, `admonitions`, `spoiler`, `task_list`, `strike`,
+    /// `tables`, `wiki-tables`, `target-blank-links` and `nofollow`. Extras for which the feature is off are left out.
+    pub fn markdown2_compat() -> Self {
+        let wiki = Self::new().html(true).strikethrough(true).tables(true);
+        #[cfg(feature = "wikitable")]
+        let wiki = wiki.wiki_tables(WikiTableOptions::default());
+        #[cfg(feature = "latex")]
+        let wiki = wiki.latex(true);
+        #[cfg(feature = "footnote")]
+        let wiki = wiki.footnotes(FootnoteOptions::default());
+        #[cfg(feature = "admonition")]
+        let wiki = wiki.admonitions(AdmonitionOptions::default());
+        #[cfg(feature = "spoiler")]
+        let wiki = wiki.spoilers(SpoilerOptions::default());
+        #[cfg(feature = "tasklist")]
+        let wiki = wiki.task_lists(TaskListOptions::default());
+        #[cfg(feature = "linkattrs")]
+        let wiki = wiki.link_attributes(LinkAttributesOptions::default());
+        #[cfg(feature = "toc")]
+        let wiki = wiki.toc(TOCOptions {
+            slugger: Box::new(Markdown2Slugger),
+            duplicate_suffix_start: 2,
+            ..TOCOptions::default()
+        });
+        wiki
+    }
+
+    /// Raw HTML blocks and inline tags, passed through as they are, like markdown2 does without `safe_mode`.
+    pub fn html(mut self, enabled: bool) -> Self {
+        self.html = enabled;
+        self
+    }
+
+    /// `~~strikethrough~~`, from markdown-it's extras.
+    pub fn strikethrough(mut self, enabled: bool) -> Self {
+        self.strikethrough = enabled;
+        self
+    }
+
+    /// GitHub flavoured tables, from markdown-it's extras.
+    pub fn tables(mut self, enabled: bool) -> Self {
+        self.tables = enabled;
+        self
+    }
+
+    /// markdown2's `|| a || b ||` tables.
+    #[cfg(feature = "wikitable")]
+    pub fn wiki_tables(mut self, options: WikiTableOptions) -> Self {
+        self.wiki_tables = Some(options);
+        self
+    }
+
+    #[cfg(feature = "wikitable")]
+    pub fn without_wiki_tables(mut self) -> Self {
+        self.wiki_tables = None;
+        self
+    }
+
+    #[cfg(feature = "latex")]
+    pub fn latex(mut self, enabled: bool) -> Self {
+        self.latex = enabled;
+        self
+    }
+
+    /// `^superscript^` and `~subscript~`. markdown2 has no extra for these, so `markdown2_compat()` leaves
+    /// them out.
+    #[cfg(feature = "supsub")]
+    pub fn supsub(mut self, options: SupSubOptions) -> Self {
+        self.supsub = Some(options);
+        self
+    }
+
+    #[cfg(feature = "supsub")]
+    pub fn without_supsub(mut self) -> Self {
+        self.supsub = None;
+        self
+    }
+
+    /// `loading="lazy"` on images. Not a markdown2 extra either.
+    #[cfg(feature = "lazyload")]
+    pub fn lazyload(mut self, options: LazyLoadOptions) -> Self {
+        self.lazyload = Some(options);
+        self
+    }
+
+    #[cfg(feature = "lazyload")]
+    pub fn without_lazyload(mut self) -> Self {
+        self.lazyload = None;
+        self
+    }
+
+    /// Curly quotes, dashes and ellipses. Like markdown2's `smarty-pants` extra, which the wiki doesn't use, so
+    /// `markdown2_compat()` leaves it out.
+    #[cfg(feature = "smartypants")]
+    pub fn smartypants(mut self, options: SmartyPantsOptions) -> Self {
+        self.smartypants = Some(options);
+        self
+    }
+
+    #[cfg(feature = "smartypants")]
+    pub fn without_smartypants(mut self) -> Self {
+        self.smartypants = None;
+        self
+    }
+
+    /// `target`, `rel` and a class on links leading away from the site.
+    #[cfg(feature = "linkattrs")]
+    pub fn link_attributes(mut self, options: LinkAttributesOptions) -> Self {
+        self.link_attributes = Some(options);
+        self
+    }
+
+    #[cfg(feature = "linkattrs")]
+    pub fn without_link_attributes(mut self) -> Self {
+        self.link_attributes = None;
+        self
+    }
+
+    /// `!!! note` blocks and GitHub's `> [!NOTE]` alerts.
+    #[cfg(feature = "admonition")]
+    pub fn admonitions(mut self, options: AdmonitionOptions) -> Self {
+        self.admonitions = Some(options);
+        self
+    }
+
+    #[cfg(feature = "admonition")]
+    pub fn without_admonitions(mut self) -> Self {
+        self.admonitions = None;
+        self
+    }
+
+    /// `>! block` and `||inline||` spoilers.
+    #[cfg(feature = "spoiler")]
+    pub fn spoilers(mut self, options: SpoilerOptions) -> Self {
+        self.spoilers = Some(options);
+        self
+    }
+
+    #[cfg(feature = "spoiler")]
+    pub fn without_spoilers(mut self) -> Self {
+        self.spoilers = None;
+        self
+    }
+
+    /// `- [ ]` and `- [x]` tasks.
+    #[cfg(feature = "tasklist")]
+    pub fn task_lists(mut self, options: TaskListOptions) -> Self {
+        self.task_lists = Some(options);
+        self
+    }
+
+    #[cfg(feature = "tasklist")]
+    pub fn without_task_lists(mut self) -> Self {
+        self.task_lists = None;
+        self
+    }
+
+    #[cfg(feature = "footnote")]
+    pub fn footnotes(mut self, options: FootnoteOptions) -> Self {
+        self.footnotes = Some(options);
+        self
+    }
+
+    #[cfg(feature = "footnote")]
+    pub fn without_footnotes(mut self) -> Self {
+        self.footnotes = None;
+        self
+    }
+
+    #[cfg(feature = "toc")]
+    pub fn toc(mut self, options: TOCOptions) -> Self {
+        self.toc = Some(options);
+        self
+    }
+
+    #[cfg(feature = "toc")]
+    pub fn without_toc(mut self) -> Self {
+        self.toc = None;
+        self
+    }
+
+    pub fn build(self) -> MarkdownIt {
+        let mut md = MarkdownIt::new();
+        markdown_it::plugins::cmark::add(&mut md);
+        if self.html {
+            markdown_it::plugins::html::add(&mut md);
+        }
+        if self.strikethrough {
+            markdown_it::plugins::extra::strikethrough::add(&mut md);
+        }
+        if self.tables {
+            markdown_it::plugins::extra::tables::add(&mut md);
+        }
+        #[cfg(feature = "wikitable")]
+        if let Some(options) = self.wiki_tables {
+            markdown_it_wikitable::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "latex")]
+        if self.latex {
+            markdown_it_latex::add(&mut md);
+        }
+        #[cfg(feature = "admonition")]
+        if let Some(options) = self.admonitions {
+            markdown_it_admonition::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "spoiler")]
+        if let Some(options) = self.spoilers {
+            markdown_it_spoiler::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "tasklist")]
+        if let Some(options) = self.task_lists {
+            markdown_it_tasklist::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "supsub")]
+        if let Some(options) = self.supsub {
+            markdown_it_supsub::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "lazyload")]
+        if let Some(options) = self.lazyload {
+            markdown_it_lazyload::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "smartypants")]
+        if let Some(options) = self.smartypants {
+            markdown_it_smartypants::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "linkattrs")]
+        if let Some(options) = self.link_attributes {
+            markdown_it_link_attributes::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "footnote")]
+        if let Some(options) = self.footnotes {
+            markdown_it_footnotes::add_with_options(&mut md, options);
+        }
+        #[cfg(feature = "toc")]
+        if let Some(options) = self.toc {
+            markdown_it_table_of_contents::add_with_options(&mut md, options);
+        }
+        md
+    }
+}
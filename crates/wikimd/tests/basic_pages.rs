@@ -0,0 +1,41 @@
+use testing::Test;
+use wikimd::WikiMd;
+
+// The fixtures use every plugin.
+#[cfg(all(feature = "toc", feature = "footnote", feature = "latex", feature = "admonition", feature = "spoiler", feature = "tasklist", feature = "wikitable", feature = "linkattrs"))]
+#[rstest::rstest]
+fn main(#[files("tests/predone/*.md")] path: std::path::PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let parser = WikiMd::markdown2_compat().build();
+    assert!(test.output_matches_log(&parser));
+}
+
+// Plugins markdown2_compat() leaves out, which have to get along with each other and with raw HTML as well.
+#[cfg(all(feature = "lazyload", feature = "smartypants"))]
+#[rstest::rstest]
+fn extras(#[files("tests/extras/*.md")] path: std::path::PathBuf) {
+    let test = Test::from_file(path).unwrap();
+    let parser = WikiMd::new().html(true).lazyload(Default::default()).smartypants(Default::default()).build();
+    assert!(test.output_matches_log(&parser));
+}
+
+#[test]
+fn plain() {
+    let input = "# Lorem ~~ipsum~~ $x$[^1]\n";
+    assert_eq!(
+        WikiMd::new().build().parse(input).render(),
+        Test::default_parser().parse(input).render()
+    );
+}
+
+#[test]
+fn properties() {
+    let wiki = WikiMd::markdown2_compat();
+    #[cfg(feature = "supsub")]
+    let wiki = wiki.supsub(Default::default());
+    #[cfg(feature = "lazyload")]
+    let wiki = wiki.lazyload(Default::default());
+    #[cfg(feature = "smartypants")]
+    let wiki = wiki.smartypants(Default::default());
+    testing::assert_properties(&wiki.build());
+}
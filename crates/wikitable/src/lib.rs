@@ -0,0 +1,164 @@
+use markdown_it::{
+    MarkdownIt, Node,
+    common::sourcemap::SourcePos,
+    parser::{
+        block::{ BlockRule, BlockState },
+        extset::MarkdownItExt,
+        inline::InlineRoot
+    },
+    plugins::{
+        cmark::block::paragraph::ParagraphScanner,
+        extra::tables::{ ColumnAlignment, Table, TableBody, TableCell, TableHead, TableRow, TableScanner }
+    }
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct WikiTableOptions {
+    pub alignment: bool // align columns by how the cells of the first row are padded. markdown2 doesn't, so turning
+                        // this off renders older pages exactly as it did.
+}
+
+impl Default for WikiTableOptions {
+    fn default() -> Self {
+        Self { alignment: true }
+    }
+}
+impl MarkdownItExt for WikiTableOptions {}
+
+struct Cell<'a> {
+    content: &'a str,
+    offset: usize, // of the content in the line.
+    header: bool,
+    alignment: ColumnAlignment
+}
+
+// Content which sticks to one side of its cell is aligned to that side, and content with two spaces or more on
+// both sides is centered: `||left  ||`, `||  right||`, `||  center  ||`. A space on either side, as in `|| a ||`,
+// is just padding.
+fn alignment(cell: &str) -> ColumnAlignment {
+    let leading = cell.len() - cell.trim_start_matches([' ', '\t']).len();
+    let trailing = cell.len() - cell.trim_end_matches([' ', '\t']).len();
+    match (leading >= 2, trailing >= 2) {
+        (true, true) => ColumnAlignment::Center,
+        (true, false) => ColumnAlignment::Right,
+        (false, true) => ColumnAlignment::Left,
+        (false, false) => ColumnAlignment::None
+    }
+}
+
+// Whether what follows `before` is escaped: it is after an odd number of backslashes, since `\\` is a backslash
+// which doesn't escape anything.
+fn escaped(before: &str) -> bool {
+    (before.len() - before.trim_end_matches('\\').len()) % 2 == 1
+}
+
+// Splits a row into its cells. A row starts and ends with `||`, and its cells are separated by `||` which isn't
+// escaped with a backslash. A cell starting with `~` is a header cell.
+fn scan_row(line: &str) -> Option<Vec<Cell<'_>>> {
+    let inner = line.trim_end().strip_prefix("||")?.strip_suffix("||")?;
+    if inner.is_empty() || escaped(inner) { return None; }
+
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    while pos <= inner.len() {
+        let separator = inner[pos..].starts_with("||") && !escaped(&inner[..pos]);
+        if pos == inner.len() || separator {
+            let raw = &inner[start..pos];
+            let marker = raw.len() - raw.trim_start().len();
+            let header = raw[marker..].starts_with('~');
+            let (padded, padded_start) = if header { (&raw[marker + 1..], start + marker + 1) } else { (raw, start) };
+            let content = padded.trim();
+            cells.push(Cell {
+                content,
+                offset: 2 + padded_start + padded.len() - padded.trim_start().len(),
+                header,
+                alignment: alignment(padded)
+            });
+            pos += 2;
+            start = pos;
+        } else {
+            pos += inner[pos..].chars().next().unwrap().len_utf8();
+        }
+    }
+    Some(cells)
+}
+
+// markdown2's `wiki-tables`: rows of `|| a || b ||` on consecutive lines. Like in markdown2, only the first row can
+// be a header, which it is if its first cell is one.
+struct WikiTableScanner;
+
+impl WikiTableScanner {
+    fn row<'a>(state: &'a BlockState, line: usize) -> Option<Vec<Cell<'a>>> {
+        if state.line_indent(line) < 0 || state.line_indent(line) >= 4 { return None; }
+        scan_row(state.get_line(line))
+    }
+
+    fn row_node(state: &BlockState, line: usize, cells: Vec<Cell>) -> Node {
+        let mut row = Node::new(TableRow);
+        row.srcmap = state.get_map(line, line);
+        let line_start = state.line_offsets[line].first_nonspace;
+        for cell in cells {
+            let start = line_start + cell.offset;
+            let mut node = Node::new(TableCell);
+            node.srcmap = Some(SourcePos::new(start, start + cell.content.len()));
+            if !cell.content.is_empty() {
+                node.children.push(Node::new(InlineRoot::new(cell.content.to_string(), vec![(0, start)])));
+            }
+            row.children.push(node);
+        }
+        row
+    }
+}
+
+impl BlockRule for WikiTableScanner {
+    // Like in markdown2, a table needs a blank line before it, so a line of a paragraph starting with `||` stays
+    // part of it.
+    fn check(_: &mut BlockState) -> Option<()> {
+        None
+    }
+
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        let first = Self::row(state, state.line)?;
+        let options = state.md.ext.get::<WikiTableOptions>().unwrap();
+        let alignments = match options.alignment {
+            true => first.iter().map(|cell| cell.alignment).collect(),
+            false => Vec::new()
+        };
+        let mut table = Node::new(Table { alignments });
+
+        let start = state.line;
+        let mut body_start = start;
+        if first[0].header {
+            let mut head = Node::new(TableHead);
+            head.srcmap = state.get_map(start, start);
+            head.children.push(Self::row_node(state, start, first));
+            table.children.push(head);
+            body_start += 1;
+        }
+
+        let mut body = Node::new(TableBody);
+        let mut end = body_start;
+        while end < state.line_max {
+            let Some(cells) = Self::row(state, end) else { break };
+            body.children.push(Self::row_node(state, end, cells));
+            end += 1;
+        }
+        if !body.children.is_empty() {
+            body.srcmap = state.get_map(body_start, end - 1);
+            table.children.push(body);
+        }
+
+        Some((table, end - start))
+    }
+}
+
+pub fn add(md: &mut MarkdownIt) {
+    add_with_options(md, WikiTableOptions::default());
+}
+
+pub fn add_with_options(md: &mut MarkdownIt, options: WikiTableOptions) {
+    md.ext.insert(options);
+    md.block.add_rule::<WikiTableScanner>().before::<TableScanner>().before::<ParagraphScanner>();
+}
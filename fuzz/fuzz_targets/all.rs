@@ -0,0 +1,20 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use testing::Test;
+
+// Every plugin at once, to catch them getting in each other's way.
+fuzz_target!(|input: &str| {
+    let mut parser = Test::default_parser();
+    markdown_it_table_of_contents::add(&mut parser);
+    markdown_it_footnotes::add(&mut parser);
+    markdown_it_latex::add(&mut parser);
+    markdown_it_supsub::add(&mut parser);
+    markdown_it_lazyload::add(&mut parser);
+    markdown_it_admonition::add(&mut parser);
+    markdown_it_spoiler::add(&mut parser);
+    markdown_it_tasklist::add(&mut parser);
+    markdown_it_smartypants::add(&mut parser);
+    markdown_it_wikitable::add(&mut parser);
+    markdown_it_link_attributes::add(&mut parser);
+    testing::check(&parser, input).unwrap();
+});
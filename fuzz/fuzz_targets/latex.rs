@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use testing::Test;
+
+fuzz_target!(|input: &str| {
+    let mut parser = Test::default_parser();
+    markdown_it_latex::add(&mut parser);
+    testing::check(&parser, input).unwrap();
+});